@@ -0,0 +1,111 @@
+//! Whole-catalog workloads on a synthetic catalog.
+//!
+//! The fixture `benches/fixtures/catalog.tle` is generated with
+//! `cargo run --example generate-catalog > benches/fixtures/catalog.tle`.
+
+#[macro_use]
+extern crate criterion;
+use criterion::{Criterion, Throughput};
+
+use std::io::BufReader;
+
+use tletools::{Format, Reader, TLE};
+
+const CATALOG: &str = include_str!("fixtures/catalog.tle");
+
+fn catalog() -> Vec<TLE> {
+    tletools::parse_all(CATALOG).map(Result::unwrap).collect()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Catalog/Parse");
+    group.throughput(Throughput::Bytes(CATALOG.len() as u64));
+
+    group.bench_function("parse records", |b| {
+        let lines: Vec<&str> = CATALOG.lines().collect();
+        b.iter(|| {
+            lines
+                .chunks_exact(3)
+                .map(|record| tletools::from_lines(record[0], record[1], record[2]).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("parse_all", |b| {
+        b.iter(|| {
+            tletools::parse_all(CATALOG)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("Reader (BufReader)", |b| {
+        b.iter(|| {
+            Reader::new(BufReader::new(CATALOG.as_bytes()))
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+fn format(c: &mut Criterion) {
+    let tles = catalog();
+    let mut group = c.benchmark_group("Catalog/Format");
+    group.throughput(Throughput::Elements(tles.len() as u64));
+
+    group.bench_function("to_string_in", |b| {
+        b.iter(|| {
+            tles.iter()
+                .map(|tle| tle.to_string_in(Format::ThreeLine))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("write_all", |b| {
+        let mut buf = Vec::with_capacity(CATALOG.len());
+        b.iter(|| {
+            buf.clear();
+            tletools::write_all(&mut buf, &tles, Format::ThreeLine).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+#[cfg(feature = "sgp4")]
+fn propagate(c: &mut Criterion) {
+    let tles = catalog();
+    let mut group = c.benchmark_group("Catalog/Propagate");
+
+    group.throughput(Throughput::Elements(tles.len() as u64));
+    group.bench_function("propagator", |b| {
+        b.iter(|| {
+            tles.iter()
+                .map(|tle| tle.propagator().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    // one day every 10 minutes
+    let propagators: Vec<_> = tles.iter().map(|tle| tle.propagator().unwrap()).collect();
+    group.throughput(Throughput::Elements(tles.len() as u64 * 144));
+    group.bench_function("propagate one day", |b| {
+        b.iter(|| {
+            for propagator in &propagators {
+                for step in 0..144 {
+                    propagator.propagate(step as f64 * 10.0).unwrap();
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+#[cfg(feature = "sgp4")]
+criterion_group!(benches, parse, format, propagate);
+#[cfg(not(feature = "sgp4"))]
+criterion_group!(benches, parse, format);
+criterion_main!(benches);
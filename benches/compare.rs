@@ -48,5 +48,40 @@ fn compare(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare);
+fn decode(c: &mut Criterion) {
+    let records = [
+        (
+            "ISS (ZARYA)",
+            "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990",
+            "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791",
+        ),
+        (
+            "VANGUARD 1",
+            "1 00005U 58002B   20045.59766236  .00000028  00000-0  39591-4 0  9995",
+            "2 00005  34.2467 209.5404 1846039 214.0436 132.2098 10.84868778205428",
+        ),
+    ];
+
+    let mut group = c.benchmark_group("Decode");
+
+    group.bench_with_input("tletools::from_lines", &records, |b, records| {
+        b.iter(|| {
+            for (name, line1, line2) in records {
+                tletools::from_lines(name, line1, line2).unwrap();
+            }
+        })
+    });
+
+    group.bench_with_input("tletools::fast::from_lines", &records, |b, records| {
+        b.iter(|| {
+            for (name, line1, line2) in records {
+                tletools::fast::from_lines(name, line1, line2).unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, compare, decode);
 criterion_main!(benches);
@@ -3,6 +3,15 @@
 // which does not unnecessarily print
 // https://github.com/pbrandt1/rust-tle/blob/master/src/lib.rs#L113
 
+// kept close to the upstream code for a fair comparison, so its lints are not fixed
+#![allow(
+    clippy::assign_op_pattern,
+    clippy::is_digit_ascii_radix,
+    clippy::needless_borrow,
+    clippy::upper_case_acronyms,
+    clippy::useless_format
+)]
+
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Debug)]
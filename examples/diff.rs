@@ -0,0 +1,21 @@
+//! Compare two TLE catalogs: `cargo run --example diff OLD NEW`.
+
+use std::env;
+use std::error::Error;
+
+use tletools::compressed;
+use tletools::diff::diff_catalogs;
+use tletools::TLE;
+
+fn read(path: &str) -> Result<Vec<TLE>, Box<dyn Error>> {
+    Ok(compressed::open(path)?.collect::<Result<_, _>>()?)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [old, new] = args.as_slice() else {
+        return Err("usage: diff OLD NEW".into());
+    };
+    println!("{}", diff_catalogs(&read(old)?, &read(new)?));
+    Ok(())
+}
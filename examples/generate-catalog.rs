@@ -0,0 +1,85 @@
+//! Generate a synthetic catalog of near-Earth objects:
+//! `cargo run --example generate-catalog [COUNT] > benches/fixtures/catalog.tle`.
+//!
+//! The generator is deterministic, so the fixtures of the benchmarks can be
+//! regenerated identically.
+
+use std::env;
+use std::error::Error;
+use std::io;
+
+use tletools::{Classification, EphemerisType, Format, IntDesig, TLE};
+
+/// Default number of records.
+const COUNT: u32 = 2000;
+
+/// Linear congruential generator (Knuth's MMIX constants).
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    /// Integer in `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    /// Value in `[lo, hi]` with the given number of decimals.
+    fn fixed(&mut self, lo: f64, hi: f64, decimals: i32) -> f64 {
+        let scale = 10f64.powi(decimals);
+        let (lo, hi) = ((lo * scale) as u64, (hi * scale) as u64);
+        (lo + self.below(hi - lo + 1)) as f64 / scale
+    }
+
+    /// Value in the decimal-point-assumed exponential notation.
+    fn exp_decimal(&mut self, exponents: std::ops::RangeInclusive<i32>) -> f64 {
+        let m = 10_000 + self.below(90_000) as i32;
+        let e =
+            exponents.start() + self.below((exponents.end() - exponents.start() + 1) as u64) as i32;
+        m as f64 * 10f64.powi(e - 5)
+    }
+}
+
+fn generate(rng: &mut Lcg, i: u32) -> TLE {
+    let norad = 10_000 + i;
+    let launch_year = 1990 + (i % 30) as i32;
+    TLE {
+        name: format!("SYNTH-{:05}", norad),
+        norad: norad.to_string(),
+        classification: Classification::Unclassified,
+        int_desig: IntDesig::new(launch_year, 1 + (i % 120) as u16, "A")
+            .ok()
+            .into(),
+        epoch_year: 2020,
+        epoch_day: rng.fixed(40.0, 50.0, 8),
+        dn_o2: rng.fixed(0.0, 0.0001, 8),
+        ddn_o6: 0.0,
+        bstar: rng.exp_decimal(-5..=-3),
+        ephem_type: EphemerisType::default(),
+        set_num: rng.below(1000) as u32,
+        inc: rng.fixed(0.0, 110.0, 4),
+        raan: rng.fixed(0.0, 359.9999, 4),
+        ecc: rng.fixed(0.0, 0.02, 7),
+        argp: rng.fixed(0.0, 359.9999, 4),
+        M: rng.fixed(0.0, 359.9999, 4),
+        n: rng.fixed(12.0, 16.0, 8),
+        rev_num: rng.below(100_000) as u32,
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let count = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => COUNT,
+    };
+    let mut rng = Lcg(0x5eed);
+    let tles: Vec<TLE> = (0..count).map(|i| generate(&mut rng, i)).collect();
+    tletools::write_all(io::stdout().lock(), &tles, Format::ThreeLine)?;
+    Ok(())
+}
@@ -1,27 +1,40 @@
-use std::fs;
-use std::io::{self, prelude::*, BufReader};
+//! Read all the TLEs contained in the files given on the command line.
+//!
+//! The files can be plain text, gzip or xz compressed, or zip archives.
 
-use itertools::Itertools;
+use std::env;
+use std::error::Error;
 
-use tletools;
+use tletools::compressed::{self, ZipReader};
+use tletools::TLE;
 
-fn main() -> io::Result<()> {
-    for date_folder in fs::read_dir("data")? {
-        for filename in fs::read_dir(date_folder?.path())? {
-            let filename = filename?;
-            println!("\n{:?}", filename.path());
-            let file = fs::File::open(filename.path())?;
-            let buf_reader = BufReader::new(file);
-            for mut chunk in &buf_reader.lines().chunks(3) {
-                let name = chunk.next().unwrap()?;
-                let line1 = chunk.next().unwrap()?;
-                let line2 = chunk.next().unwrap()?;
-                // let tle_str = [name.as_str(), line1.as_str(), line2.as_str()].join("\n");
-                let tle = tletools::from_lines(name.as_str(), line1.as_str(), line2.as_str());
-                let sgp = sgp4::Elements::from_tle(Some(name), line1.as_bytes(), line2.as_bytes())
-                    .unwrap();
-                println!("{:#?}", tle);
-                println!("{}", serde_json::to_string(&sgp).unwrap());
+fn print(source: &str, tle: &TLE) {
+    println!("\n{}", source);
+    println!("{:#?}", tle);
+    let sgp = sgp4::Elements::from_tle(
+        Some(tle.name.clone()),
+        tle.line1().as_bytes(),
+        tle.line2().as_bytes(),
+    )
+    .unwrap();
+    println!("{}", serde_json::to_string(&sgp).unwrap());
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    for path in env::args().skip(1) {
+        if path.ends_with(".zip") {
+            for record in ZipReader::open(&path)? {
+                match record {
+                    Ok(record) => print(&format!("{}: {}", path, record.member), &record.tle),
+                    Err(e) => eprintln!("{}: {}", path, e),
+                }
+            }
+        } else {
+            for tle in compressed::open(&path)? {
+                match tle {
+                    Ok(tle) => print(&path, &tle),
+                    Err(e) => eprintln!("{}: {}", path, e),
+                }
             }
         }
     }
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|lines: (&str, &str, &str)| {
+    let (name, line1, line2) = lines;
+    let _ = tletools::from_lines(name, line1, line2);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = tletools::parse_bytes(data);
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = tletools::parse(s);
+    }
+});
@@ -0,0 +1,33 @@
+//! Parsing and formatting of TLEs in a `no_std` crate.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use tletools::{from_lines, Error, TLE};
+
+/// Parse a TLE from its lines.
+pub fn parse(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
+    from_lines(name, line1, line2)
+}
+
+/// Format the two element lines of a TLE.
+pub fn format(tle: &TLE) -> (String, String) {
+    (tle.line1(), tle.line2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990";
+    const LINE2: &str = "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_round_trip() {
+        let tle = parse("ISS (ZARYA)", LINE1, LINE2).unwrap();
+        assert_eq!(format(&tle), (LINE1.into(), LINE2.into()));
+    }
+}
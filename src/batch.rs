@@ -0,0 +1,236 @@
+//! SGP4 propagation of many objects at many times.
+//!
+//! A [`BatchPropagator`] initializes the SGP4 state of each object once and
+//! propagates all of them on a common time grid, returning the positions and
+//! velocities as a structure of arrays ([`BatchStates`]). Objects whose
+//! elements are invalid, or which decay during the interval, do not stop the
+//! propagation of the others: their errors are reported in
+//! [`BatchStates::errors`] and their missing states are NaN.
+//!
+//! With the `rayon` feature, [`BatchPropagator::par_propagate`] propagates
+//! the objects in parallel.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{PropagationError, Propagator, StateVector, TLE};
+
+/// Error of the propagation of an object of a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectError {
+    /// Index of the object in the batch.
+    pub object: usize,
+    /// Index of the time at which the propagation failed, or `None` if the
+    /// propagator could not be initialized.
+    pub time: Option<usize>,
+    /// The error.
+    pub error: PropagationError,
+}
+
+/// States of the objects of a batch at the times of a grid, as a structure of arrays.
+///
+/// The state of object `i` at time `j` is at index `i * times() + j` of the arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchStates {
+    objects: usize,
+    times: usize,
+    /// Components of the positions (km), NaN where the propagation failed.
+    pub position: [Vec<f64>; 3],
+    /// Components of the velocities (km/s), NaN where the propagation failed.
+    pub velocity: [Vec<f64>; 3],
+    /// Errors, at most one per object, in the order of the objects.
+    pub errors: Vec<ObjectError>,
+}
+
+impl BatchStates {
+    /// Number of objects.
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Number of times.
+    pub fn times(&self) -> usize {
+        self.times
+    }
+
+    /// Index of the state of the given object at the given time in the arrays.
+    pub fn index(&self, object: usize, time: usize) -> usize {
+        assert!(object < self.objects && time < self.times);
+        object * self.times + time
+    }
+
+    /// State of the given object at the given time, if the propagation succeeded.
+    pub fn state(&self, object: usize, time: usize) -> Option<StateVector> {
+        let i = self.index(object, time);
+        let state = StateVector {
+            position: [0, 1, 2].map(|k| self.position[k][i]),
+            velocity: [0, 1, 2].map(|k| self.velocity[k][i]),
+        };
+        (!state.position[0].is_nan()).then_some(state)
+    }
+
+    fn new(objects: usize, times: usize) -> BatchStates {
+        let array = || Vec::with_capacity(objects * times);
+        BatchStates {
+            objects,
+            times,
+            position: [array(), array(), array()],
+            velocity: [array(), array(), array()],
+            errors: Vec::new(),
+        }
+    }
+
+    /// Append the states of the next object.
+    fn push(&mut self, (states, error): (Vec<StateVector>, Option<ObjectError>)) {
+        let nan = StateVector {
+            position: [f64::NAN; 3],
+            velocity: [f64::NAN; 3],
+        };
+        for i in 0..self.times {
+            let state = states.get(i).unwrap_or(&nan);
+            for k in 0..3 {
+                self.position[k].push(state.position[k]);
+                self.velocity[k].push(state.velocity[k]);
+            }
+        }
+        self.errors.extend(error);
+    }
+}
+
+/// Propagator of a batch of objects.
+pub struct BatchPropagator {
+    propagators: Vec<Result<Propagator, PropagationError>>,
+}
+
+impl BatchPropagator {
+    /// Initialize the SGP4 state of each TLE.
+    ///
+    /// A TLE whose propagator cannot be initialized is kept in the batch,
+    /// and its error is reported by each propagation.
+    pub fn new(tles: &[TLE]) -> BatchPropagator {
+        BatchPropagator {
+            propagators: tles.iter().map(TLE::propagator).collect(),
+        }
+    }
+
+    /// Number of objects.
+    pub fn len(&self) -> usize {
+        self.propagators.len()
+    }
+
+    /// Whether the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.propagators.is_empty()
+    }
+
+    /// Propagate all the objects to the given Julian dates (UTC).
+    pub fn propagate(&self, times: &[f64]) -> BatchStates {
+        let mut states = BatchStates::new(self.len(), times.len());
+        for object in 0..self.len() {
+            states.push(self.propagate_object(object, times));
+        }
+        states
+    }
+
+    /// Propagate all the objects to the given Julian dates (UTC), in parallel.
+    ///
+    /// The result is the same as that of [`BatchPropagator::propagate`].
+    #[cfg(feature = "rayon")]
+    pub fn par_propagate(&self, times: &[f64]) -> BatchStates {
+        let rows: Vec<_> = (0..self.len())
+            .into_par_iter()
+            .map(|object| self.propagate_object(object, times))
+            .collect();
+        let mut states = BatchStates::new(self.len(), times.len());
+        for row in rows {
+            states.push(row);
+        }
+        states
+    }
+
+    /// States of an object until the first error, and the error.
+    fn propagate_object(
+        &self,
+        object: usize,
+        times: &[f64],
+    ) -> (Vec<StateVector>, Option<ObjectError>) {
+        let propagator = match &self.propagators[object] {
+            Ok(propagator) => propagator,
+            Err(error) => {
+                let error = ObjectError {
+                    object,
+                    time: None,
+                    error: error.clone(),
+                };
+                return (Vec::new(), Some(error));
+            }
+        };
+        let mut states = Vec::with_capacity(times.len());
+        for (time, &jd) in times.iter().enumerate() {
+            match propagator.propagate_to(jd) {
+                Ok(state) => states.push(state),
+                Err(error) => {
+                    let error = ObjectError {
+                        object,
+                        time: Some(time),
+                        error,
+                    };
+                    return (states, Some(error));
+                }
+            }
+        }
+        (states, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_batch() {
+        let iss = crate::parse(ISS).unwrap();
+        let invalid = TLE {
+            ecc: 1.5,
+            ..crate::parse(ISS).unwrap()
+        };
+        // decays quickly
+        let decaying = TLE {
+            n: 16.5,
+            bstar: 0.5,
+            ..crate::parse(ISS).unwrap()
+        };
+        let epoch = iss.epoch_julian_date();
+        let times: Vec<f64> = (0..50).map(|i| epoch + i as f64).collect();
+
+        let batch = BatchPropagator::new(&[iss, invalid, decaying]);
+        let states = batch.propagate(&times);
+        assert_eq!((states.objects(), states.times()), (3, 50));
+        assert_eq!(states.position[0].len(), 150);
+
+        let iss = crate::parse(ISS).unwrap().propagator().unwrap();
+        for (j, &jd) in times.iter().enumerate() {
+            assert_eq!(states.state(0, j), Some(iss.propagate_to(jd).unwrap()));
+            assert_eq!(states.state(1, j), None);
+        }
+
+        assert_eq!(states.errors.len(), 2);
+        assert_eq!((states.errors[0].object, states.errors[0].time), (1, None));
+        assert_eq!(states.errors[0].error.kind.code(), 1);
+        let decay = &states.errors[1];
+        let t = decay.time.unwrap();
+        assert_eq!(decay.object, 2);
+        assert!(t > 0 && states.state(2, t - 1).is_some() && states.state(2, t).is_none());
+
+        #[cfg(feature = "rayon")]
+        {
+            let par = batch.par_propagate(&times);
+            assert_eq!(par.errors, states.errors);
+            assert_eq!(par.state(0, 7), states.state(0, 7));
+        }
+    }
+}
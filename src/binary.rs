@@ -0,0 +1,589 @@
+//! Compact binary encoding of TLE archives.
+//!
+//! The encoding stores a sequence of [`ExactTLE`], so decoding reproduces the
+//! exact text of the lines. The records are grouped by object and each group
+//! can be decoded independently of the others through an [`Archive`].
+//!
+//! # Layout (version 1)
+//!
+//! Integers marked as varint use the LEB128 encoding, signed ones after the
+//! zigzag mapping.
+//!
+//! - Header: the magic bytes `TLEB`, the version byte and the number of objects (varint).
+//! - Index: for each object, the Norad number (varint length and bytes),
+//!   the number of records (varint) and the length in bytes of its block (varint).
+//! - Blocks: the records of each object, in the order of the index. Each record is made of
+//!   - a byte of flags, telling whether the name and the international designator
+//!     differ from those of the previous record of the object, followed by the
+//!     new values (varint length and bytes);
+//!   - the differences of the epoch year and day (in units of 10⁻⁸ day) from
+//!     those of the previous record (signed varints);
+//!   - the remaining fields, packed in 276 bits padded to 35 bytes.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use thiserror::Error;
+
+use crate::{Classification, EphemerisType, ExactTLE, ExpDecimal, IntDesigField};
+
+/// Magic bytes at the start of an encoded archive.
+pub const MAGIC: [u8; 4] = *b"TLEB";
+
+/// Version of the encoding produced by [`encode`].
+pub const VERSION: u8 = 1;
+
+const NAME_CHANGED: u8 = 0b01;
+const INT_DESIG_CHANGED: u8 = 0b10;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    #[error("{field} of object {norad} does not fit in its columns")]
+    FieldOutOfRange { norad: String, field: &'static str },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("invalid magic bytes")]
+    InvalidMagic,
+    #[error("unsupported version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("invalid data, reason: {0}")]
+    InvalidData(&'static str),
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_signed(out: &mut Vec<u8>, n: i64) {
+    write_varint(out, zigzag(n));
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Writer of fixed-width fields, most significant bit first.
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    acc: u64,
+    len: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(out: &'a mut Vec<u8>) -> BitWriter<'a> {
+        BitWriter {
+            out,
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    fn write(&mut self, value: u64, bits: u32) {
+        debug_assert!(bits <= 56 && value >> bits == 0);
+        self.acc = self.acc << bits | value;
+        self.len += bits;
+        while self.len >= 8 {
+            self.len -= 8;
+            self.out.push((self.acc >> self.len) as u8);
+        }
+    }
+
+    fn finish(mut self) {
+        if self.len > 0 {
+            self.write(0, 8 - self.len);
+        }
+    }
+}
+
+/// Reader of encoded data.
+struct Cursor<'a> {
+    data: &'a [u8],
+    acc: u64,
+    len: u32,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Cursor<'a> {
+        Cursor {
+            data,
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (&b, rest) = self.data.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        self.data = rest;
+        Ok(b)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if n > self.data.len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut n = 0;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            n |= ((b & 0x7f) as u64) << shift;
+            if b < 0x80 {
+                return Ok(n);
+            }
+        }
+        Err(DecodeError::InvalidData("varint too long"))
+    }
+
+    fn signed(&mut self) -> Result<i64, DecodeError> {
+        self.varint().map(unzigzag)
+    }
+
+    fn string(&mut self) -> Result<&'a str, DecodeError> {
+        let len = self.varint()? as usize;
+        core::str::from_utf8(self.bytes(len)?)
+            .map_err(|_| DecodeError::InvalidData("invalid UTF-8"))
+    }
+
+    /// Read a fixed-width field, most significant bit first.
+    fn bits(&mut self, bits: u32) -> Result<u64, DecodeError> {
+        while self.len < bits {
+            self.acc = self.acc << 8 | self.byte()? as u64;
+            self.len += 8;
+        }
+        self.len -= bits;
+        Ok((self.acc >> self.len) & ((1 << bits) - 1))
+    }
+
+    /// Discard the padding of the packed fields.
+    fn align(&mut self) {
+        self.acc = 0;
+        self.len = 0;
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+fn write_exp(w: &mut BitWriter, x: ExpDecimal) {
+    w.write(zigzag(x.mantissa as i64), 18);
+    w.write(x.exponent.unsigned_abs() as u64, 4);
+    w.write(x.negative_exponent as u64, 1);
+}
+
+fn read_exp(c: &mut Cursor) -> Result<ExpDecimal, DecodeError> {
+    let mantissa = unzigzag(c.bits(18)?) as i32;
+    let exponent = c.bits(4)? as i8;
+    let negative_exponent = c.bits(1)? == 1;
+    if mantissa.unsigned_abs() > 99_999 || exponent > 9 {
+        return Err(DecodeError::InvalidData("exponential notation"));
+    }
+    Ok(ExpDecimal {
+        mantissa,
+        exponent: if negative_exponent {
+            -exponent
+        } else {
+            exponent
+        },
+        negative_exponent,
+    })
+}
+
+/// First field which does not fit in the columns of a TLE, and thus in the packed fields.
+fn field_out_of_range(tle: &ExactTLE) -> Option<&'static str> {
+    let checks = [
+        ("epoch_day", tle.epoch_day <= 99_999_999_999),
+        ("dn_o2", tle.dn_o2.unsigned_abs() <= 99_999_999),
+        ("ddn_o6", tle.ddn_o6.mantissa.unsigned_abs() <= 99_999),
+        ("bstar", tle.bstar.mantissa.unsigned_abs() <= 99_999),
+        ("ephem_type", tle.ephem_type.code().is_ascii()),
+        ("set_num", tle.set_num <= 9_999),
+        ("inc", tle.inc <= 9_999_999),
+        ("raan", tle.raan <= 9_999_999),
+        ("ecc", tle.ecc <= 9_999_999),
+        ("argp", tle.argp <= 9_999_999),
+        ("M", tle.M <= 9_999_999),
+        ("n", tle.n <= 9_999_999_999),
+        ("rev_num", tle.rev_num <= 99_999),
+    ];
+    checks.iter().find(|(_, ok)| !ok).map(|&(field, _)| field)
+}
+
+fn encode_record(out: &mut Vec<u8>, tle: &ExactTLE, prev: Option<&ExactTLE>) {
+    let int_desig = |tle: &ExactTLE| tle.int_desig.to_string();
+    let mut flags = 0;
+    if prev.is_none_or(|p| p.name != tle.name) {
+        flags |= NAME_CHANGED;
+    }
+    if prev.is_none_or(|p| p.int_desig != tle.int_desig) {
+        flags |= INT_DESIG_CHANGED;
+    }
+    out.push(flags);
+    if flags & NAME_CHANGED != 0 {
+        write_bytes(out, tle.name.as_bytes());
+    }
+    if flags & INT_DESIG_CHANGED != 0 {
+        write_bytes(out, int_desig(tle).as_bytes());
+    }
+
+    let (year, day) = prev.map_or((0, 0), |p| (p.epoch_year, p.epoch_day));
+    write_signed(out, tle.epoch_year as i64 - year as i64);
+    write_signed(out, tle.epoch_day as i64 - day as i64);
+
+    let mut w = BitWriter::new(out);
+    w.write(
+        match tle.classification {
+            Classification::Unclassified => 0,
+            Classification::Classified => 1,
+            Classification::Secret => 2,
+        },
+        2,
+    );
+    w.write(zigzag(tle.dn_o2), 35);
+    write_exp(&mut w, tle.ddn_o6);
+    write_exp(&mut w, tle.bstar);
+    w.write(tle.ephem_type.code() as u64, 8);
+    w.write(tle.set_num as u64, 14);
+    for angle in [tle.inc, tle.raan, tle.ecc, tle.argp, tle.M] {
+        w.write(angle as u64, 24);
+    }
+    w.write(tle.n, 34);
+    w.write(tle.rev_num as u64, 17);
+    w.finish();
+}
+
+fn decode_record(
+    c: &mut Cursor,
+    norad: &str,
+    prev: Option<&ExactTLE>,
+) -> Result<ExactTLE, DecodeError> {
+    let flags = c.byte()?;
+    let name = match (flags & NAME_CHANGED != 0, prev) {
+        (true, _) => c.string()?.to_string(),
+        (false, Some(p)) => p.name.clone(),
+        (false, None) => return Err(DecodeError::InvalidData("missing name")),
+    };
+    let int_desig = match (flags & INT_DESIG_CHANGED != 0, prev) {
+        (true, _) => IntDesigField::from_columns(c.string()?.as_bytes()),
+        (false, Some(p)) => p.int_desig.clone(),
+        (false, None) => return Err(DecodeError::InvalidData("missing designator")),
+    };
+
+    let (year, day) = prev.map_or((0, 0), |p| (p.epoch_year, p.epoch_day));
+    let epoch_year = (year as i64)
+        .checked_add(c.signed()?)
+        .and_then(|y| i32::try_from(y).ok())
+        .ok_or(DecodeError::InvalidData("epoch year"))?;
+    let epoch_day = (day as i64)
+        .checked_add(c.signed()?)
+        .and_then(|d| u64::try_from(d).ok())
+        .ok_or(DecodeError::InvalidData("epoch day"))?;
+
+    let classification = match c.bits(2)? {
+        0 => Classification::Unclassified,
+        1 => Classification::Classified,
+        2 => Classification::Secret,
+        _ => return Err(DecodeError::InvalidData("classification")),
+    };
+    let dn_o2 = unzigzag(c.bits(35)?);
+    let ddn_o6 = read_exp(c)?;
+    let bstar = read_exp(c)?;
+    let ephem_type = EphemerisType::try_from(c.bits(8)? as u8 as char)
+        .map_err(|_| DecodeError::InvalidData("ephemeris type"))?;
+    let set_num = c.bits(14)? as u32;
+    let mut angles = [0; 5];
+    for angle in &mut angles {
+        *angle = c.bits(24)? as u32;
+    }
+    let [inc, raan, ecc, argp, m] = angles;
+    let n = c.bits(34)?;
+    let rev_num = c.bits(17)? as u32;
+    c.align();
+
+    let tle = ExactTLE {
+        name,
+        norad: norad.to_string(),
+        classification,
+        int_desig,
+        epoch_year,
+        epoch_day,
+        dn_o2,
+        ddn_o6,
+        bstar,
+        ephem_type,
+        set_num,
+        inc,
+        raan,
+        ecc,
+        argp,
+        M: m,
+        n,
+        rev_num,
+    };
+    match field_out_of_range(&tle) {
+        Some(field) => Err(DecodeError::InvalidData(field)),
+        None => Ok(tle),
+    }
+}
+
+/// Encode a sequence of TLEs.
+///
+/// The records are grouped by object, in the order of first appearance;
+/// the records of each object keep their relative order.
+pub fn encode<'a, I>(tles: I) -> Result<Vec<u8>, EncodeError>
+where
+    I: IntoIterator<Item = &'a ExactTLE>,
+{
+    let mut index: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut objects: Vec<(&str, Vec<&ExactTLE>)> = Vec::new();
+    for tle in tles {
+        if let Some(field) = field_out_of_range(tle) {
+            return Err(EncodeError::FieldOutOfRange {
+                norad: tle.norad.clone(),
+                field,
+            });
+        }
+        let i = *index.entry(&tle.norad).or_insert_with(|| {
+            objects.push((&tle.norad, Vec::new()));
+            objects.len() - 1
+        });
+        objects[i].1.push(tle);
+    }
+
+    let blocks: Vec<Vec<u8>> = objects
+        .iter()
+        .map(|(_, records)| {
+            let mut block = Vec::new();
+            let mut prev = None;
+            for &tle in records {
+                encode_record(&mut block, tle, prev);
+                prev = Some(tle);
+            }
+            block
+        })
+        .collect();
+
+    let mut out = Vec::from(MAGIC);
+    out.push(VERSION);
+    write_varint(&mut out, objects.len() as u64);
+    for ((norad, records), block) in objects.iter().zip(&blocks) {
+        write_bytes(&mut out, norad.as_bytes());
+        write_varint(&mut out, records.len() as u64);
+        write_varint(&mut out, block.len() as u64);
+    }
+    for block in blocks {
+        out.extend_from_slice(&block);
+    }
+    Ok(out)
+}
+
+/// Decode all the records of an encoded archive, grouped by object.
+pub fn decode(data: &[u8]) -> Result<Vec<ExactTLE>, DecodeError> {
+    let archive = Archive::new(data)?;
+    let mut tles = Vec::new();
+    for norad in archive.objects() {
+        tles.extend(archive.get(norad).unwrap()?);
+    }
+    Ok(tles)
+}
+
+/// Entry of the index of an archive.
+#[derive(Debug)]
+struct Entry<'a> {
+    records: usize,
+    block: &'a [u8],
+}
+
+/// Encoded archive, giving access to the records of each object.
+#[derive(Debug)]
+pub struct Archive<'a> {
+    norads: Vec<&'a str>,
+    entries: BTreeMap<&'a str, Entry<'a>>,
+}
+
+impl<'a> Archive<'a> {
+    /// Read the header and the index of an encoded archive.
+    pub fn new(data: &'a [u8]) -> Result<Archive<'a>, DecodeError> {
+        let mut c = Cursor::new(data);
+        if c.bytes(MAGIC.len())
+            .map_err(|_| DecodeError::InvalidMagic)?
+            != MAGIC
+        {
+            return Err(DecodeError::InvalidMagic);
+        }
+        let version = c.byte()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let count = c.varint()? as usize;
+        let mut index = Vec::with_capacity(count.min(data.len()));
+        for _ in 0..count {
+            let norad = c.string()?;
+            let records = c.varint()? as usize;
+            let len = c.varint()? as usize;
+            index.push((norad, records, len));
+        }
+
+        let mut norads = Vec::with_capacity(index.len());
+        let mut entries = BTreeMap::new();
+        for (norad, records, len) in index {
+            let block = c.bytes(len)?;
+            if entries.insert(norad, Entry { records, block }).is_some() {
+                return Err(DecodeError::InvalidData("duplicate object"));
+            }
+            norads.push(norad);
+        }
+        Ok(Archive { norads, entries })
+    }
+
+    /// Norad numbers of the objects, in the order in which they are stored.
+    pub fn objects(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.norads.iter().copied()
+    }
+
+    /// Number of records of the given object.
+    pub fn len_of(&self, norad: &str) -> Option<usize> {
+        self.entries.get(norad).map(|e| e.records)
+    }
+
+    /// Decode the records of the given object, or return `None` if it is not in the archive.
+    pub fn get(&self, norad: &str) -> Option<Result<Vec<ExactTLE>, DecodeError>> {
+        let (&norad, entry) = self.entries.get_key_value(norad)?;
+        let mut c = Cursor::new(entry.block);
+        let mut tles: Vec<ExactTLE> = Vec::with_capacity(entry.records.min(entry.block.len()));
+        for _ in 0..entry.records {
+            match decode_record(&mut c, norad, tles.last()) {
+                Ok(tle) => tles.push(tle),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(tles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: [[&str; 3]; 4] = [
+        [
+            "ISS (ZARYA)",
+            "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990",
+            "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791",
+        ],
+        [
+            "VANGUARD 1",
+            "1 00005U 58002B   20045.59766236  .00000028  00000+0  39591-4 0  9995",
+            "2 00005  34.2467 209.5404 1846039 214.0436 132.2098 10.84868778205423",
+        ],
+        [
+            "ISS (ZARYA)",
+            "1 25544U 98067A   20046.18587073 -.00000950  00000-0 -25302-4 0  9993",
+            "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212803",
+        ],
+        [
+            "DECAYING",
+            "1 99999S          20001.00000000 -.00012345 -12345-6 -10000+1 9  9997",
+            "2 99999 180.0000 359.9999 9999999   0.0000   0.0001 99.99999999999998",
+        ],
+    ];
+
+    fn catalog() -> Vec<ExactTLE> {
+        CATALOG
+            .iter()
+            .map(|[name, line1, line2]| ExactTLE::from_lines(name, line1, line2).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tles = catalog();
+        let data = encode(&tles).unwrap();
+        let text: usize = CATALOG.iter().flatten().map(|l| l.len() + 1).sum();
+        assert!(data.len() * 2 < text);
+
+        let decoded = decode(&data).unwrap();
+        assert_eq!(decoded.len(), tles.len());
+        for (tle, [name, line1, line2]) in decoded.iter().zip([0, 2, 1, 3].map(|i| CATALOG[i])) {
+            assert_eq!(tle.name, name);
+            assert_eq!(tle.line1(), line1);
+            assert_eq!(tle.line2(), line2);
+        }
+    }
+
+    #[test]
+    fn test_random_access() {
+        let tles = catalog();
+        let data = encode(&tles).unwrap();
+        let archive = Archive::new(&data).unwrap();
+        assert_eq!(
+            archive.objects().collect::<Vec<_>>(),
+            ["25544", "00005", "99999"]
+        );
+        assert_eq!(archive.len_of("25544"), Some(2));
+        assert_eq!(archive.get("00005").unwrap().unwrap(), [tles[1].clone()]);
+        assert!(archive.get("12345").is_none());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let data = encode(&catalog()).unwrap();
+        assert_eq!(decode(b"TLE"), Err(DecodeError::InvalidMagic));
+        assert_eq!(decode(b"TLEB\x02"), Err(DecodeError::UnsupportedVersion(2)));
+        for len in 5..data.len() {
+            assert!(decode(&data[..len]).is_err());
+        }
+
+        let mut tle = catalog().remove(0);
+        tle.inc = 10_000_000;
+        assert_eq!(
+            encode([&tle]),
+            Err(EncodeError::FieldOutOfRange {
+                norad: "25544".into(),
+                field: "inc"
+            })
+        );
+        tle.inc = 0;
+        tle.dn_o2 = 100_000_000;
+        assert!(encode([&tle]).is_err());
+
+        // a record whose fields fit in the packed bits but not in the columns
+        for (field, set) in [
+            ("inc", (|tle| tle.inc = 16_777_215) as fn(&mut ExactTLE)),
+            ("set_num", |tle| tle.set_num = 16_383),
+            ("rev_num", |tle| tle.rev_num = 131_071),
+        ] {
+            let mut tle = catalog().remove(0);
+            set(&mut tle);
+            let mut block = Vec::new();
+            encode_record(&mut block, &tle, None);
+            let mut data = Vec::from(MAGIC);
+            data.push(VERSION);
+            write_varint(&mut data, 1);
+            write_bytes(&mut data, tle.norad.as_bytes());
+            write_varint(&mut data, 1);
+            write_varint(&mut data, block.len() as u64);
+            data.extend_from_slice(&block);
+            assert_eq!(decode(&data), Err(DecodeError::InvalidData(field)));
+        }
+    }
+}
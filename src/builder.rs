@@ -0,0 +1,358 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use thiserror::Error;
+
+use crate::epoch::is_leap_year;
+use crate::{
+    Classification, EncodePolicy, EphemerisType, FormatError, IntDesig, IntDesigField, TLE,
+};
+
+/// Mean orbital elements of a TLE, in the units of the TLE format.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanElements {
+    /// Inclination (degrees, `[0, 180]`).
+    pub inc: f64,
+    /// Right ascension of the ascending node (degrees, `[0, 360)`).
+    pub raan: f64,
+    /// Eccentricity (`[0, 1)`).
+    pub ecc: f64,
+    /// Argument of perigee (degrees, `[0, 360)`).
+    pub argp: f64,
+    /// Mean anomaly (degrees, `[0, 360)`).
+    pub M: f64,
+    /// Mean motion (revolutions per day, positive).
+    pub n: f64,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BuildError {
+    #[error("invalid catalog number {0:?}")]
+    InvalidNorad(String),
+    #[error("name contains a line break")]
+    InvalidName,
+    #[error("epoch year {0} out of range [1957, 2056]")]
+    EpochYearOutOfRange(i32),
+    #[error("{field} {value} out of range")]
+    OutOfRange { field: &'static str, value: f64 },
+    #[error("value cannot be written in a TLE")]
+    NotEncodable(#[from] FormatError),
+}
+
+/// Builder of a [`TLE`] from its elements.
+///
+/// The catalog number, the epoch and the mean elements are required; the other
+/// fields default to an unclassified object without name and international
+/// designator, without drag terms, with the element set number and the
+/// revolution number `0`. [`TLEBuilder::build`] checks that the values
+/// are in their ranges and that they can be written in the TLE columns with the
+/// policy given to [`TLEBuilder::policy`]. By default, the `ddn_o6` and `bstar`
+/// values are rounded to the 5-digit mantissa of their columns, so that computed
+/// values are accepted as long as their exponent fits.
+///
+/// ```
+/// use tletools::{MeanElements, TLEBuilder};
+///
+/// let elements = MeanElements {
+///     inc: 51.6443,
+///     raan: 242.0161,
+///     ecc: 0.0004885,
+///     argp: 264.6060,
+///     M: 207.3845,
+///     n: 15.49165514,
+/// };
+/// let tle = TLEBuilder::new("25544", 2020, 45.18587073, elements)
+///     .name("ISS (ZARYA)")
+///     .bstar(2.5302e-5)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     tle.line2(),
+///     "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514    00"
+/// );
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct TLEBuilder {
+    tle: TLE,
+    policy: EncodePolicy,
+}
+
+impl TLEBuilder {
+    /// Start building the TLE of the given object at the given epoch.
+    ///
+    /// The catalog number has up to five digits, or is in the Alpha-5 form
+    /// (a letter other than `I` and `O` followed by four digits).
+    pub fn new(norad: &str, epoch_year: i32, epoch_day: f64, elements: MeanElements) -> TLEBuilder {
+        TLEBuilder {
+            tle: TLE {
+                norad: norad.to_string(),
+                epoch_year,
+                epoch_day,
+                inc: elements.inc,
+                raan: elements.raan,
+                ecc: elements.ecc,
+                argp: elements.argp,
+                M: elements.M,
+                n: elements.n,
+                ..TLE::default()
+            },
+            policy: EncodePolicy {
+                round_mantissas: true,
+                ..EncodePolicy::STRICT
+            },
+        }
+    }
+
+    /// Name of the object.
+    pub fn name(mut self, name: &str) -> TLEBuilder {
+        self.tle.name = name.to_string();
+        self
+    }
+
+    /// Classification.
+    pub fn classification(mut self, classification: Classification) -> TLEBuilder {
+        self.tle.classification = classification;
+        self
+    }
+
+    /// International designator.
+    pub fn int_desig(mut self, int_desig: IntDesig) -> TLEBuilder {
+        self.tle.int_desig = IntDesigField::Parsed(int_desig);
+        self
+    }
+
+    /// First time-derivative of the mean motion divided by 2 (revolutions per day²).
+    pub fn dn_o2(mut self, dn_o2: f64) -> TLEBuilder {
+        self.tle.dn_o2 = dn_o2;
+        self
+    }
+
+    /// Second time-derivative of the mean motion divided by 6 (revolutions per day³).
+    pub fn ddn_o6(mut self, ddn_o6: f64) -> TLEBuilder {
+        self.tle.ddn_o6 = ddn_o6;
+        self
+    }
+
+    /// BSTAR drag term (1/earth radii).
+    pub fn bstar(mut self, bstar: f64) -> TLEBuilder {
+        self.tle.bstar = bstar;
+        self
+    }
+
+    /// Ephemeris type.
+    pub fn ephem_type(mut self, ephem_type: EphemerisType) -> TLEBuilder {
+        self.tle.ephem_type = ephem_type;
+        self
+    }
+
+    /// Element set number.
+    pub fn set_num(mut self, set_num: u32) -> TLEBuilder {
+        self.tle.set_num = set_num;
+        self
+    }
+
+    /// Revolution number at the epoch.
+    pub fn rev_num(mut self, rev_num: u32) -> TLEBuilder {
+        self.tle.rev_num = rev_num;
+        self
+    }
+
+    /// Policy with which the values are checked against the TLE columns.
+    ///
+    /// With [`EncodePolicy::STRICT`], the `ddn_o6` and `bstar` values must be
+    /// exactly representable with a 5-digit mantissa.
+    pub fn policy(mut self, policy: EncodePolicy) -> TLEBuilder {
+        self.policy = policy;
+        self
+    }
+
+    /// Check the values and build the TLE.
+    ///
+    /// A purely numeric catalog number is padded with zeros to five digits. The
+    /// values rounded or wrapped by the policy are stored as they are written,
+    /// so that the TLE is equal to the one read back from its lines.
+    pub fn build(self) -> Result<TLE, BuildError> {
+        let TLEBuilder { mut tle, policy } = self;
+
+        let norad = tle.norad.as_bytes();
+        let numeric = !norad.is_empty() && norad.len() <= 5 && norad.iter().all(u8::is_ascii_digit);
+        let alpha5 = norad.len() == 5
+            && norad[0].is_ascii_uppercase()
+            && !matches!(norad[0], b'I' | b'O')
+            && norad[1..].iter().all(u8::is_ascii_digit);
+        if numeric {
+            tle.norad = format!("{:0>5}", tle.norad);
+        } else if !alpha5 {
+            return Err(BuildError::InvalidNorad(tle.norad));
+        }
+
+        if tle.name.contains(['\n', '\r']) {
+            return Err(BuildError::InvalidName);
+        }
+        if !(1957..=2056).contains(&tle.epoch_year) {
+            return Err(BuildError::EpochYearOutOfRange(tle.epoch_year));
+        }
+
+        let out_of_range = |field, value: f64, ok: bool| {
+            if ok {
+                Ok(())
+            } else {
+                Err(BuildError::OutOfRange { field, value })
+            }
+        };
+        let angle = |x: f64| (0.0..360.0).contains(&x);
+        let days = if is_leap_year(tle.epoch_year) {
+            366.0
+        } else {
+            365.0
+        };
+        out_of_range(
+            "epoch_day",
+            tle.epoch_day,
+            (1.0..days + 1.0).contains(&tle.epoch_day),
+        )?;
+        out_of_range("inc", tle.inc, (0.0..=180.0).contains(&tle.inc))?;
+        out_of_range("raan", tle.raan, angle(tle.raan))?;
+        out_of_range("ecc", tle.ecc, (0.0..1.0).contains(&tle.ecc))?;
+        out_of_range("argp", tle.argp, angle(tle.argp))?;
+        out_of_range("M", tle.M, angle(tle.M))?;
+        out_of_range("n", tle.n, tle.n > 0.0)?;
+        for (field, value) in [
+            ("dn_o2", tle.dn_o2),
+            ("ddn_o6", tle.ddn_o6),
+            ("bstar", tle.bstar),
+        ] {
+            out_of_range(field, value, value.is_finite())?;
+        }
+
+        Ok(tle.encoded(policy)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: MeanElements = MeanElements {
+        inc: 51.6443,
+        raan: 242.0161,
+        ecc: 0.0004885,
+        argp: 264.6060,
+        M: 207.3845,
+        n: 15.49165514,
+    };
+
+    #[test]
+    fn test_build() {
+        let tle = TLEBuilder::new("25544", 2020, 45.18587073, ISS)
+            .name("ISS (ZARYA)")
+            .int_desig(IntDesig::new(1998, 67, "A").unwrap())
+            .dn_o2(0.0000095)
+            .bstar(2.5302e-5)
+            .set_num(999)
+            .rev_num(21279)
+            .build()
+            .unwrap();
+        let parsed = crate::parse(
+            "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791",
+        )
+        .unwrap();
+        assert_eq!(tle, parsed);
+
+        let tle = TLEBuilder::new("5", 1958, 1.0, ISS).build().unwrap();
+        assert_eq!(tle.norad, "00005");
+        assert_eq!(tle.classification, Classification::Unclassified);
+        assert_eq!(tle.set_num, 0);
+        assert_eq!(
+            TLEBuilder::new("A1234", 2020, 1.0, ISS)
+                .build()
+                .unwrap()
+                .norad,
+            "A1234"
+        );
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let build = |norad| TLEBuilder::new(norad, 2020, 1.0, ISS);
+        for norad in ["", "123456", "I1234", "a1234", "AB123"] {
+            assert_eq!(
+                build(norad).build(),
+                Err(BuildError::InvalidNorad(norad.into()))
+            );
+        }
+        assert!(TLEBuilder::new("1", 2020, 366.5, ISS).build().is_ok());
+        assert_eq!(
+            TLEBuilder::new("1", 2021, 366.5, ISS).build(),
+            Err(BuildError::OutOfRange {
+                field: "epoch_day",
+                value: 366.5
+            })
+        );
+        assert_eq!(
+            TLEBuilder::new("1", 2057, 1.0, ISS).build(),
+            Err(BuildError::EpochYearOutOfRange(2057))
+        );
+        assert_eq!(
+            TLEBuilder::new("1", 2020, 1.0, MeanElements { ecc: 1.0, ..ISS }).build(),
+            Err(BuildError::OutOfRange {
+                field: "ecc",
+                value: 1.0
+            })
+        );
+        assert_eq!(
+            TLEBuilder::new("1", 2020, 1.0, MeanElements { n: 100.0, ..ISS }).build(),
+            Err(BuildError::NotEncodable(FormatError::MeanMotionTooLarge(
+                100.0
+            )))
+        );
+        assert!(build("1").bstar(0.99999e9).build().is_ok());
+        assert!(build("1").bstar(0.999996e9).build().is_err());
+        assert!(build("1").bstar(-1e-10).build().is_ok());
+        assert!(build("1").bstar(1e10).build().is_err());
+        // computed values are rounded, unless the policy is strict
+        assert!(build("1").bstar(1e-15).build().is_ok());
+        assert!(build("1").bstar(1.234567e-5).build().is_ok());
+        let strict = |builder: TLEBuilder| builder.policy(EncodePolicy::STRICT).build();
+        assert!(strict(build("1").bstar(1e-15)).is_err());
+        assert!(strict(build("1").bstar(1.234567e-5)).is_err());
+        assert!(strict(build("1").bstar(1.2345e-5)).is_ok());
+        assert!(build("1").dn_o2(-0.99999999).build().is_ok());
+        assert!(build("1").dn_o2(1.0).build().is_err());
+        assert!(build("1").set_num(10000).build().is_err());
+        assert!(build("1").rev_num(100000).build().is_err());
+        assert_eq!(
+            build("1").name("A\nB").build(),
+            Err(BuildError::InvalidName)
+        );
+        for builder in [
+            build("1").dn_o2(f64::NAN),
+            build("1").ddn_o6(f64::INFINITY),
+            build("1").bstar(f64::NAN),
+        ] {
+            assert!(matches!(
+                builder.build(),
+                Err(BuildError::OutOfRange { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_lines_round_trip() {
+        let build = |builder: TLEBuilder| {
+            let tle = builder.build().unwrap();
+            let parsed = crate::from_lines(&tle.name, &tle.line1(), &tle.line2()).unwrap();
+            assert_eq!(parsed, tle);
+            tle
+        };
+        let builder = || TLEBuilder::new("25544", 2020, 45.18587073, ISS);
+        assert_eq!(build(builder().bstar(1e-15)).bstar, 0.0);
+        assert_eq!(build(builder().bstar(1.234567e-5)).bstar, 1.2346e-5);
+        assert_eq!(build(builder().ddn_o6(-9.876543e-9)).ddn_o6, -9.8765e-9);
+        let lenient = builder().rev_num(123456).policy(EncodePolicy::LENIENT);
+        assert_eq!(build(lenient).rev_num, 23456);
+    }
+}
@@ -0,0 +1,266 @@
+//! Reading of compressed TLE files and archives.
+//!
+//! [`decompress`] and [`open`] recognise gzip and xz data from their magic
+//! bytes and feed the decompressed text to a [`Reader`]; data that is not
+//! compressed is read as it is. The [`ZipReader`] reads the records of all
+//! the members of a zip archive, attaching the name of the member to each
+//! of them.
+//!
+//! Each compression format requires the corresponding feature: `gzip`, `xz` or `zip`.
+
+use std::fs::File;
+#[cfg(feature = "zip")]
+use std::io::Seek;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+#[cfg(feature = "zip")]
+use thiserror::Error;
+
+use crate::reader::Reader;
+#[cfg(feature = "zip")]
+use crate::{reader::ReadError, TLE};
+
+/// Compression format of some data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Uncompressed data.
+    None,
+    /// gzip, possibly made of several members.
+    Gzip,
+    /// xz, possibly made of several streams.
+    Xz,
+}
+
+impl Compression {
+    /// Recognise the compression format from the first bytes of the data.
+    pub fn detect(data: &[u8]) -> Compression {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if data.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Read the TLE records of possibly compressed data, recognising its compression format.
+///
+/// Fails with [`io::ErrorKind::Unsupported`] if the feature for the format is not enabled.
+pub fn decompress<'a, R: BufRead + 'a>(mut reader: R) -> io::Result<Reader<Box<dyn BufRead + 'a>>> {
+    // a short read could return fewer bytes than the longest magic number
+    let mut magic = Vec::with_capacity(6);
+    (&mut reader).take(6).read_to_end(&mut magic)?;
+    let compression = Compression::detect(&magic);
+    let reader = Cursor::new(magic).chain(reader);
+    let reader: Box<dyn BufRead + 'a> = match compression {
+        Compression::None => Box::new(reader),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Box::new(BufReader::new(xz2::bufread::XzDecoder::new_multi_decoder(
+            reader,
+        ))),
+        #[allow(unreachable_patterns)]
+        compression => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{:?} compression is not enabled", compression),
+            ))
+        }
+    };
+    Ok(Reader::new(reader))
+}
+
+/// Read the TLE records of a possibly compressed file, recognising its compression format.
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Reader<Box<dyn BufRead>>> {
+    decompress(BufReader::new(File::open(path)?))
+}
+
+#[cfg(feature = "zip")]
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("invalid zip archive")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("cannot read member {member}")]
+    MemberError {
+        /// Name of the member of the archive.
+        member: String,
+        #[source]
+        source: ReadError,
+    },
+}
+
+/// TLE read from a member of an archive.
+#[cfg(feature = "zip")]
+#[derive(Debug, PartialEq)]
+pub struct ArchiveRecord {
+    /// Name of the member of the archive.
+    pub member: String,
+    /// The TLE.
+    pub tle: TLE,
+}
+
+/// Iterator over the TLE records of all the members of a zip archive.
+///
+/// The members are read in the order in which they are stored; directories are skipped.
+#[cfg(feature = "zip")]
+pub struct ZipReader<R> {
+    archive: zip::ZipArchive<R>,
+    index: usize,
+    member: String,
+    reader: Option<Reader<Cursor<Vec<u8>>>>,
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> ZipReader<R> {
+    /// Create a reader of the given zip archive.
+    pub fn new(reader: R) -> Result<ZipReader<R>, ArchiveError> {
+        Ok(ZipReader {
+            archive: zip::ZipArchive::new(reader)?,
+            index: 0,
+            member: String::new(),
+            reader: None,
+        })
+    }
+
+    /// Names of the members of the archive.
+    pub fn member_names(&self) -> impl Iterator<Item = &str> {
+        self.archive.file_names()
+    }
+
+    /// Decompress the next member which is not a directory, returning `false` at the end.
+    fn next_member(&mut self) -> Result<bool, ArchiveError> {
+        while self.index < self.archive.len() {
+            let mut file = self.archive.by_index(self.index)?;
+            self.index += 1;
+            if file.is_dir() {
+                continue;
+            }
+            self.member = file.name().to_string();
+            // the size in the header is not trusted for the allocation
+            let mut content = Vec::with_capacity(file.size().min(1 << 20) as usize);
+            file.read_to_end(&mut content)
+                .map_err(|e| ArchiveError::MemberError {
+                    member: self.member.clone(),
+                    source: e.into(),
+                })?;
+            self.reader = Some(Reader::new(Cursor::new(content)));
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl ZipReader<BufReader<File>> {
+    /// Open the zip archive at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ZipReader<BufReader<File>>, ArchiveError> {
+        let file = File::open(path).map_err(zip::result::ZipError::Io)?;
+        ZipReader::new(BufReader::new(file))
+    }
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> Iterator for ZipReader<R> {
+    type Item = Result<ArchiveRecord, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.reader.as_mut().and_then(Iterator::next) {
+                return Some(match result {
+                    Ok(tle) => Ok(ArchiveRecord {
+                        member: self.member.clone(),
+                        tle,
+                    }),
+                    Err(source) => Err(ArchiveError::MemberError {
+                        member: self.member.clone(),
+                        source,
+                    }),
+                });
+            }
+            self.reader = None;
+            match self.next_member() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791
+";
+
+    fn names(data: &[u8]) -> Vec<String> {
+        decompress(data).unwrap().map(|r| r.unwrap().name).collect()
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(names(ISS.as_bytes()), ["ISS (ZARYA)"]);
+        assert_eq!(Compression::detect(ISS.as_bytes()), Compression::None);
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 8]), Compression::Gzip);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        use std::io::Write;
+
+        let mut data = Vec::new();
+        // two concatenated gzip members
+        for _ in 0..2 {
+            let mut encoder = flate2::write::GzEncoder::new(&mut data, Default::default());
+            encoder.write_all(ISS.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+        assert_eq!(names(&data), ["ISS (ZARYA)", "ISS (ZARYA)"]);
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn test_xz() {
+        use std::io::Write;
+
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(ISS.as_bytes()).unwrap();
+        let data = encoder.finish().unwrap();
+        assert_eq!(names(&data), ["ISS (ZARYA)"]);
+        // the magic bytes arrive one at a time
+        let reader = decompress(BufReader::with_capacity(1, &data[..])).unwrap();
+        assert_eq!(reader.count(), 1);
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_zip() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("2020", options).unwrap();
+        writer.start_file("2020/a.txt", options).unwrap();
+        writer.write_all(ISS.as_bytes()).unwrap();
+        writer.start_file("2020/b.txt", options).unwrap();
+        writer.write_all(b"BROKEN\n").unwrap();
+        writer.write_all(ISS.as_bytes()).unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let results: Vec<_> = ZipReader::new(Cursor::new(data)).unwrap().collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().member, "2020/a.txt");
+        assert!(matches!(
+            &results[1],
+            Err(ArchiveError::MemberError { member, .. }) if member == "2020/b.txt"
+        ));
+        assert_eq!(results[2].as_ref().unwrap().member, "2020/b.txt");
+    }
+}
@@ -0,0 +1,299 @@
+//! Conversion of TLE catalogs to and from CSV tables.
+//!
+//! Each [`TLE`] becomes a row with one column per field, named as the field.
+//! Floating point values are written with the shortest representation that
+//! parses back to the same value, so a table can be read back losslessly.
+//!
+//! The writer can optionally append derived columns: the epoch as an ISO 8601
+//! timestamp, the orbital period (minutes) and the apogee and perigee altitudes (km).
+//! These columns are ignored by the reader.
+
+use std::io::{Read, Write};
+
+use thiserror::Error;
+
+use crate::{IntDesigField, TLE};
+
+/// Names of the columns holding the fields of a [`TLE`].
+pub const COLUMNS: [&str; 18] = [
+    "name",
+    "norad",
+    "classification",
+    "int_desig",
+    "epoch_year",
+    "epoch_day",
+    "dn_o2",
+    "ddn_o6",
+    "bstar",
+    "ephem_type",
+    "set_num",
+    "inc",
+    "raan",
+    "ecc",
+    "argp",
+    "M",
+    "n",
+    "rev_num",
+];
+
+/// Names of the derived columns.
+pub const DERIVED_COLUMNS: [&str; 4] = ["epoch", "period", "apogee", "perigee"];
+
+#[derive(Error, Debug)]
+pub enum CsvError {
+    #[error("CSV error")]
+    CsvError(#[from] ::csv::Error),
+    #[error("missing column {0}")]
+    MissingColumn(&'static str),
+    #[error("invalid value in column {0}")]
+    InvalidValue(&'static str),
+}
+
+/// Writer of TLEs as CSV rows.
+pub struct Writer<W: Write> {
+    inner: ::csv::Writer<W>,
+    derived: bool,
+    header_written: bool,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a writer without derived columns.
+    pub fn new(writer: W) -> Writer<W> {
+        Writer {
+            inner: ::csv::Writer::from_writer(writer),
+            derived: false,
+            header_written: false,
+        }
+    }
+
+    /// Enable or disable the derived columns.
+    pub fn derived_columns(mut self, derived: bool) -> Writer<W> {
+        self.derived = derived;
+        self
+    }
+
+    fn write_header(&mut self) -> Result<(), CsvError> {
+        let derived = if self.derived {
+            &DERIVED_COLUMNS[..]
+        } else {
+            &[]
+        };
+        self.inner
+            .write_record(COLUMNS.iter().chain(derived.iter()))?;
+        self.header_written = true;
+        Ok(())
+    }
+
+    /// Write a single TLE as a row, preceded by the header if this is the first row.
+    pub fn write(&mut self, tle: &TLE) -> Result<(), CsvError> {
+        if !self.header_written {
+            self.write_header()?;
+        }
+        let mut record = vec![
+            tle.name.clone(),
+            tle.norad.clone(),
+            tle.classification.to_string(),
+            tle.int_desig.to_string(),
+            tle.epoch_year.to_string(),
+            tle.epoch_day.to_string(),
+            tle.dn_o2.to_string(),
+            tle.ddn_o6.to_string(),
+            tle.bstar.to_string(),
+            tle.ephem_type.to_string(),
+            tle.set_num.to_string(),
+            tle.inc.to_string(),
+            tle.raan.to_string(),
+            tle.ecc.to_string(),
+            tle.argp.to_string(),
+            tle.M.to_string(),
+            tle.n.to_string(),
+            tle.rev_num.to_string(),
+        ];
+        if self.derived {
+            record.push(tle.epoch_iso8601());
+            record.push(tle.period().to_string());
+            record.push(tle.apogee_altitude().to_string());
+            record.push(tle.perigee_altitude().to_string());
+        }
+        self.inner.write_record(&record)?;
+        Ok(())
+    }
+
+    /// Write all the TLEs in `tles` as rows.
+    pub fn write_all<'a, I>(&mut self, tles: I) -> Result<(), CsvError>
+    where
+        I: IntoIterator<Item = &'a TLE>,
+    {
+        for tle in tles {
+            self.write(tle)?;
+        }
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<(), CsvError> {
+        if !self.header_written {
+            self.write_header()?;
+        }
+        self.inner.flush().map_err(::csv::Error::from)?;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, CsvError> {
+        self.flush()?;
+        self.inner
+            .into_inner()
+            .map_err(|e| CsvError::CsvError(e.into_error().into()))
+    }
+}
+
+/// Reader of TLEs from CSV rows, as produced by [`Writer`].
+///
+/// The columns are matched by name, so their order is irrelevant and
+/// additional columns are ignored.
+pub struct Reader<R: Read> {
+    inner: ::csv::Reader<R>,
+    columns: Option<[usize; 18]>,
+    done: bool,
+}
+
+impl<R: Read> Reader<R> {
+    /// Create a reader; the first row must be the header.
+    pub fn new(reader: R) -> Reader<R> {
+        Reader {
+            inner: ::csv::Reader::from_reader(reader),
+            columns: None,
+            done: false,
+        }
+    }
+
+    fn columns(&mut self) -> Result<[usize; 18], CsvError> {
+        if let Some(columns) = self.columns {
+            return Ok(columns);
+        }
+        let headers = self.inner.headers()?;
+        let mut columns = [0; 18];
+        for (i, name) in COLUMNS.iter().enumerate() {
+            columns[i] = headers
+                .iter()
+                .position(|h| h == *name)
+                .ok_or(CsvError::MissingColumn(name))?;
+        }
+        self.columns = Some(columns);
+        Ok(columns)
+    }
+
+    fn read_record(&mut self, record: &::csv::StringRecord) -> Result<TLE, CsvError> {
+        let columns = self.columns()?;
+        let get = |i: usize| record.get(columns[i]).unwrap_or("");
+        fn parse<T: std::str::FromStr>(s: &str, column: &'static str) -> Result<T, CsvError> {
+            s.parse().map_err(|_| CsvError::InvalidValue(column))
+        }
+
+        fn parse_char<T: TryFrom<char>>(s: &str, column: &'static str) -> Result<T, CsvError> {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => T::try_from(c).map_err(|_| CsvError::InvalidValue(column)),
+                _ => Err(CsvError::InvalidValue(column)),
+            }
+        }
+
+        // designators which are not valid are kept as they are written
+        let int_desig = match get(3) {
+            "" => IntDesigField::Blank,
+            s => match s.parse() {
+                Ok(int_desig) => IntDesigField::Parsed(int_desig),
+                Err(_) => IntDesigField::Raw(s.to_string()),
+            },
+        };
+        Ok(TLE {
+            name: get(0).to_string(),
+            norad: get(1).to_string(),
+            classification: parse_char(get(2), COLUMNS[2])?,
+            int_desig,
+            epoch_year: parse(get(4), COLUMNS[4])?,
+            epoch_day: parse(get(5), COLUMNS[5])?,
+            dn_o2: parse(get(6), COLUMNS[6])?,
+            ddn_o6: parse(get(7), COLUMNS[7])?,
+            bstar: parse(get(8), COLUMNS[8])?,
+            ephem_type: parse_char(get(9), COLUMNS[9])?,
+            set_num: parse(get(10), COLUMNS[10])?,
+            inc: parse(get(11), COLUMNS[11])?,
+            raan: parse(get(12), COLUMNS[12])?,
+            ecc: parse(get(13), COLUMNS[13])?,
+            argp: parse(get(14), COLUMNS[14])?,
+            M: parse(get(15), COLUMNS[15])?,
+            n: parse(get(16), COLUMNS[16])?,
+            rev_num: parse(get(17), COLUMNS[17])?,
+        })
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<TLE, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Err(e) = self.columns() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        let mut record = ::csv::StringRecord::new();
+        match self.inner.read_record(&mut record) {
+            Ok(true) => Some(self.read_record(&record)),
+            Ok(false) => {
+                self.done = true;
+                None
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_round_trip() {
+        let tle = crate::parse(ISS).unwrap();
+
+        let mut writer = Writer::new(Vec::new()).derived_columns(true);
+        writer.write_all([&tle, &tle]).unwrap();
+        let buf = writer.into_inner().unwrap();
+
+        let text = std::str::from_utf8(&buf).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "name,norad,classification,int_desig,epoch_year,epoch_day,dn_o2,ddn_o6,bstar,\
+             ephem_type,set_num,inc,raan,ecc,argp,M,n,rev_num,epoch,period,apogee,perigee"
+        );
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("ISS (ZARYA),25544,U,98067A,2020,45.18587073,"));
+
+        let tles = Reader::new(&buf[..])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(tles.len(), 2);
+        assert!(tles.iter().all(|t| *t == tle));
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let mut reader = Reader::new("name,norad\nISS,25544\n".as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(CsvError::MissingColumn("classification")))
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! Differences between TLEs and between catalogs.
+//!
+//! [`TLE::diff`] compares two element sets of the same object, reporting the
+//! fields that changed and the physical meaning of the change. [`diff_catalogs`]
+//! compares two catalogs, listing the objects added, removed and updated.
+//! Both differences implement `Display`, producing a text report.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::key::norad_order;
+use crate::TLE;
+
+/// Change of a field between two TLEs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Name of the field, as in [`TLE`].
+    pub field: &'static str,
+    /// Old value.
+    pub old: String,
+    /// New value.
+    pub new: String,
+}
+
+/// Difference between two element sets of the same object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TLEDiff {
+    /// Fields that changed, in the order of the fields of [`TLE`].
+    pub changes: Vec<FieldChange>,
+    /// Time elapsed from the old epoch to the new one (days).
+    pub epoch_advance: f64,
+    /// Change of the semi-major axis (km).
+    pub delta_a: f64,
+    /// Change of the inclination (degrees).
+    pub delta_inc: f64,
+    /// Change of the right ascension of the ascending node, in `[-180, 180)` (degrees).
+    pub delta_raan: f64,
+    /// Change of the eccentricity.
+    pub delta_ecc: f64,
+}
+
+impl TLEDiff {
+    /// Whether no field changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl TLE {
+    /// Compare this TLE with a newer one.
+    pub fn diff(&self, new: &TLE) -> TLEDiff {
+        let mut changes = Vec::new();
+        let mut compare = |field, old: &dyn fmt::Display, new: &dyn fmt::Display| {
+            let (old, new) = (old.to_string(), new.to_string());
+            if old != new {
+                changes.push(FieldChange { field, old, new });
+            }
+        };
+        let int_desig = |tle: &TLE| tle.int_desig.to_string();
+        // year and day with the precision of the TLE format
+        let epoch = |tle: &TLE| format!("{} {:012.8}", tle.epoch_year, tle.epoch_day);
+
+        compare("name", &self.name, &new.name);
+        compare("norad", &self.norad, &new.norad);
+        compare("classification", &self.classification, &new.classification);
+        compare("int_desig", &int_desig(self), &int_desig(new));
+        compare("epoch", &epoch(self), &epoch(new));
+        compare("dn_o2", &self.dn_o2, &new.dn_o2);
+        compare("ddn_o6", &self.ddn_o6, &new.ddn_o6);
+        compare("bstar", &self.bstar, &new.bstar);
+        compare("ephem_type", &self.ephem_type, &new.ephem_type);
+        compare("set_num", &self.set_num, &new.set_num);
+        compare("inc", &self.inc, &new.inc);
+        compare("raan", &self.raan, &new.raan);
+        compare("ecc", &self.ecc, &new.ecc);
+        compare("argp", &self.argp, &new.argp);
+        compare("M", &self.M, &new.M);
+        compare("n", &self.n, &new.n);
+        compare("rev_num", &self.rev_num, &new.rev_num);
+
+        TLEDiff {
+            changes,
+            epoch_advance: new.epoch_julian_date() - self.epoch_julian_date(),
+            delta_a: new.semi_major_axis() - self.semi_major_axis(),
+            delta_inc: new.inc - self.inc,
+            delta_raan: (new.raan - self.raan + 180.0).rem_euclid(360.0) - 180.0,
+            delta_ecc: new.ecc - self.ecc,
+        }
+    }
+}
+
+/// Formats a one-line summary followed by the changed fields, one per line.
+impl fmt::Display for TLEDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "epoch {:+.6} d, Δa {:+.3} km, Δi {:+.4}°, Δraan {:+.4}°, Δe {:+.7}",
+            self.epoch_advance, self.delta_a, self.delta_inc, self.delta_raan, self.delta_ecc
+        )?;
+        for change in &self.changes {
+            write!(
+                f,
+                "\n    {}: {} -> {}",
+                change.field, change.old, change.new
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Difference between two catalogs.
+///
+/// The objects are identified by their Norad number and listed in the order
+/// of their catalog numbers, as in [`TLEKey`](crate::TLEKey).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogDiff<'a> {
+    /// Objects only in the new catalog.
+    pub added: Vec<&'a TLE>,
+    /// Objects only in the old catalog.
+    pub removed: Vec<&'a TLE>,
+    /// Objects in both catalogs whose element sets differ: old, new and their difference.
+    pub updated: Vec<(&'a TLE, &'a TLE, TLEDiff)>,
+}
+
+impl CatalogDiff<'_> {
+    /// Whether the catalogs contain the same element sets.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Latest element set of each object of a catalog, in the order of the catalog numbers.
+fn latest(catalog: &[TLE]) -> BTreeMap<(u32, &str), &TLE> {
+    let mut objects: BTreeMap<(u32, &str), &TLE> = BTreeMap::new();
+    for tle in catalog {
+        let entry = objects.entry(norad_order(&tle.norad)).or_insert(tle);
+        if tle.key() > entry.key() {
+            *entry = tle;
+        }
+    }
+    objects
+}
+
+/// Compare two catalogs.
+///
+/// If a catalog contains several element sets of an object, only the latest one is compared.
+pub fn diff_catalogs<'a>(old: &'a [TLE], new: &'a [TLE]) -> CatalogDiff<'a> {
+    let (old, mut new) = (latest(old), latest(new));
+    let mut diff = CatalogDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        updated: Vec::new(),
+    };
+    for (norad, old) in old {
+        match new.remove(&norad) {
+            None => diff.removed.push(old),
+            Some(new) => {
+                let d = old.diff(new);
+                if !d.is_empty() {
+                    diff.updated.push((old, new, d));
+                }
+            }
+        }
+    }
+    diff.added.extend(new.into_values());
+    diff
+}
+
+/// Formats a report with one line per object (`+` added, `-` removed, `~` updated)
+/// followed by the changed fields of the updated objects and a summary.
+impl fmt::Display for CatalogDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for tle in &self.added {
+            writeln!(f, "+ {} {}", tle.norad, tle.name)?;
+        }
+        for tle in &self.removed {
+            writeln!(f, "- {} {}", tle.norad, tle.name)?;
+        }
+        for (_, new, diff) in &self.updated {
+            writeln!(f, "~ {} {}: {}", new.norad, new.name, diff)?;
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} updated",
+            self.added.len(),
+            self.removed.len(),
+            self.updated.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_diff() {
+        let old = crate::parse(ISS).unwrap();
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = crate::parse(ISS).unwrap();
+        new.epoch_day += 1.0;
+        new.n = 15.5;
+        new.raan = 1.0;
+        let diff = old.diff(&new);
+        let fields: Vec<_> = diff.changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["epoch", "raan", "n"]);
+        assert_eq!(diff.changes[2].old, "15.49165514");
+        assert!((diff.epoch_advance - 1.0).abs() < 1e-9);
+        assert!(diff.delta_a < -2.0 && diff.delta_a > -4.0);
+        assert!((diff.delta_raan - 118.9839).abs() < 1e-9);
+        assert!(diff.to_string().ends_with("\n    n: 15.49165514 -> 15.5"));
+    }
+
+    #[test]
+    fn test_diff_catalogs() {
+        let iss = crate::parse(ISS).unwrap();
+        let mut iss_new = crate::parse(ISS).unwrap();
+        iss_new.epoch_day += 0.5;
+        let mut other = crate::parse(ISS).unwrap();
+        other.norad = "00005".into();
+        other.name = "OTHER".into();
+        let mut added = crate::parse(ISS).unwrap();
+        added.norad = "99999".into();
+
+        let old = [other, iss];
+        let new = [crate::parse(ISS).unwrap(), iss_new, added];
+        let diff = diff_catalogs(&old, &new);
+        assert_eq!(diff.added[0].norad, "99999");
+        assert_eq!(diff.removed[0].norad, "00005");
+        assert_eq!(diff.updated.len(), 1);
+        assert!((diff.updated[0].2.epoch_advance - 0.5).abs() < 1e-9);
+
+        let report = diff.to_string();
+        assert!(report.starts_with("+ 99999 ISS (ZARYA)\n- 00005 OTHER\n~ 25544 ISS (ZARYA): "));
+        assert!(report.ends_with("1 added, 1 removed, 1 updated"));
+        assert!(diff_catalogs(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! Parsers of the fixed-width numeric fields of the element lines.
+//!
+//! They work directly on bytes, without UTF-8 validation, and return `None`
+//! if the field does not have the expected form. The decimal numbers are
+//! computed as an integer divided by a power of ten, so the result is the
+//! correctly rounded value of the decimal, the same that `str::parse` gives.
+
+/// The field as an array of exactly `N` ASCII digits.
+fn all_digits<const N: usize>(s: &[u8]) -> Option<&[u8; N]> {
+    let digits: &[u8; N] = s.try_into().ok()?;
+    digits.iter().all(u8::is_ascii_digit).then_some(digits)
+}
+
+pub(crate) fn u8_2_digits(s: &[u8]) -> Option<u8> {
+    let digits = all_digits::<2>(s)?;
+    let n = (digits[0] - b'0') * 10 + (digits[1] - b'0');
+    Some(n)
+}
+
+pub(crate) fn u16_3_digits(s: &[u8]) -> Option<u16> {
+    let digits = all_digits::<3>(s)?;
+    let n = (digits[0] - b'0') as u16 * 100
+        + (digits[1] - b'0') as u16 * 10
+        + (digits[2] - b'0') as u16;
+    Some(n)
+}
+
+pub(crate) fn u16_4_digits(s: &[u8]) -> Option<u16> {
+    let digits = all_digits::<4>(s)?;
+    let n = (digits[0] - b'0') as u16 * 1000
+        + (digits[1] - b'0') as u16 * 100
+        + (digits[2] - b'0') as u16 * 10
+        + (digits[3] - b'0') as u16;
+    Some(n)
+}
+
+pub(crate) fn u32_5_digits(s: &[u8]) -> Option<u32> {
+    let digits = all_digits::<5>(s)?;
+    let n = (digits[0] - b'0') as u32 * 10000
+        + (digits[1] - b'0') as u32 * 1000
+        + (digits[2] - b'0') as u32 * 100
+        + (digits[3] - b'0') as u32 * 10
+        + (digits[4] - b'0') as u32;
+    Some(n)
+}
+
+pub(crate) fn u32_7_digits(s: &[u8]) -> Option<u32> {
+    let digits = all_digits::<7>(s)?;
+    let n = (digits[0] - b'0') as u32 * 1000000
+        + (digits[1] - b'0') as u32 * 100000
+        + (digits[2] - b'0') as u32 * 10000
+        + (digits[3] - b'0') as u32 * 1000
+        + (digits[4] - b'0') as u32 * 100
+        + (digits[5] - b'0') as u32 * 10
+        + (digits[6] - b'0') as u32;
+    Some(n)
+}
+
+pub(crate) fn u32_8_digits(s: &[u8]) -> Option<u32> {
+    let digits = all_digits::<8>(s)?;
+    let n = (digits[0] - b'0') as u32 * 10000000
+        + (digits[1] - b'0') as u32 * 1000000
+        + (digits[2] - b'0') as u32 * 100000
+        + (digits[3] - b'0') as u32 * 10000
+        + (digits[4] - b'0') as u32 * 1000
+        + (digits[5] - b'0') as u32 * 100
+        + (digits[6] - b'0') as u32 * 10
+        + (digits[7] - b'0') as u32;
+    Some(n)
+}
+
+/// Number of the form `ddd.dddd`, such as the angles of the second line.
+pub(crate) fn f64_3_4_digits(s: &[u8]) -> Option<f64> {
+    if s.len() != 8 || s[3] != b'.' {
+        return None;
+    }
+    let (a, b) = (u16_3_digits(&s[..3])?, u16_4_digits(&s[4..])?);
+    Some((a as u32 * 10000 + b as u32) as f64 / 1e4)
+}
+
+/// Number of the form `ddd.dddd` right-aligned in the field, the leading zeros being spaces.
+pub(crate) fn f64_3_4_padded(s: &[u8]) -> Option<f64> {
+    let s: &[u8; 8] = s.try_into().ok()?;
+    let mut buf = *s;
+    for c in buf.iter_mut().take_while(|c| **c == b' ') {
+        *c = b'0';
+    }
+    f64_3_4_digits(&buf)
+}
+
+/// Day of the year with its fraction, of the form `ddd.dddddddd`.
+pub(crate) fn epoch_day(s: &[u8]) -> Option<f64> {
+    if s.len() != 12 || s[3] != b'.' {
+        return None;
+    }
+    let (day, fraction) = (u16_3_digits(&s[..3])?, u32_8_digits(&s[4..])?);
+    Some((day as u64 * 100000000 + fraction as u64) as f64 / 1e8)
+}
+
+/// Value of a nonempty sequence of ASCII digits.
+fn digits(s: &[u8]) -> Option<u64> {
+    if s.is_empty() || s.len() > 15 {
+        return None;
+    }
+    s.iter().try_fold(0, |n, &c| {
+        c.is_ascii_digit().then(|| n * 10 + (c - b'0') as u64)
+    })
+}
+
+/// Strip the leading spaces and the sign, returning whether it is negative.
+fn strip_sign(s: &[u8]) -> (bool, &[u8]) {
+    split_sign(s.trim_ascii_start())
+}
+
+/// Strip the sign, returning whether it is negative.
+fn split_sign(s: &[u8]) -> (bool, &[u8]) {
+    match s.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        Some((b'+', rest)) => (false, rest),
+        _ => (false, s),
+    }
+}
+
+/// Unsigned integer right-aligned in the field.
+pub(crate) fn padded_u32(s: &[u8]) -> Option<u32> {
+    digits(s.trim_ascii_start())?.try_into().ok()
+}
+
+/// Signed integer right-aligned in the field.
+pub(crate) fn padded_i32(s: &[u8]) -> Option<i32> {
+    let (negative, s) = strip_sign(s);
+    let n: i32 = digits(s)?.try_into().ok()?;
+    Some(if negative { -n } else { n })
+}
+
+/// Signed integer filling the field, without padding.
+pub(crate) fn signed_i32(s: &[u8]) -> Option<i32> {
+    let (negative, s) = split_sign(s);
+    let n: i32 = digits(s)?.try_into().ok()?;
+    Some(if negative { -n } else { n })
+}
+
+/// Signed decimal number right-aligned in the field, with or without integer digits.
+pub(crate) fn padded_f64(s: &[u8]) -> Option<f64> {
+    let (negative, s) = strip_sign(s);
+    let (n, decimals) = match s.iter().position(|&c| c == b'.') {
+        None => (digits(s)?, 0),
+        Some(point) => {
+            let (int, frac) = (&s[..point], &s[point + 1..]);
+            if int.len() + frac.len() > 15 || (int.is_empty() && frac.is_empty()) {
+                return None;
+            }
+            let value = |d: &[u8]| if d.is_empty() { Some(0) } else { digits(d) };
+            let n = value(int)? * 10u64.pow(frac.len() as u32) + value(frac)?;
+            (n, frac.len())
+        }
+    };
+    let x = n as f64 / 10u64.pow(decimals as u32) as f64;
+    Some(if negative { -x } else { x })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f64_3_4_digits() {
+        const N: i32 = 10000;
+        for n in 0..N {
+            let x = n as f64 / N as f64;
+            let string = format!("{:08.4}", x);
+            assert_eq!(f64_3_4_digits(string.as_bytes()), Some(x));
+        }
+        for n in (0..3600000).step_by(7) {
+            let string = format!("{:8.4}", n as f64 / 1e4);
+            assert_eq!(
+                f64_3_4_padded(string.as_bytes()),
+                Some(string.trim().parse().unwrap())
+            );
+        }
+        assert_eq!(f64_3_4_padded(b"  1 .000"), None);
+        assert_eq!(f64_3_4_padded(b"   .1234"), Some(0.1234));
+        assert_eq!(f64_3_4_digits(b"123,4567"), None);
+    }
+
+    #[test]
+    fn test_u8_2_digits() {
+        for n in 0..=99 {
+            assert_eq!(u8_2_digits(format!("{:02}", n).as_bytes()), Some(n));
+        }
+        assert_eq!(u8_2_digits(b" 1"), None);
+        assert_eq!(u8_2_digits(b"123"), None);
+    }
+
+    #[test]
+    fn test_u16_3_digits() {
+        for n in 0..=999 {
+            assert_eq!(u16_3_digits(format!("{:03}", n).as_bytes()), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_u16_4_digits() {
+        for n in 0..=9999 {
+            assert_eq!(u16_4_digits(format!("{:04}", n).as_bytes()), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_u32_5_digits() {
+        for n in 0..=99999 {
+            assert_eq!(u32_5_digits(format!("{:05}", n).as_bytes()), Some(n));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_u32_7_digits() {
+        for n in (0..=9999999).skip(7) {
+            assert_eq!(u32_7_digits(format!("{:07}", n).as_bytes()), Some(n));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_u32_8_digits() {
+        for n in (0..=99999999).skip(71) {
+            assert_eq!(u32_8_digits(format!("{:08}", n).as_bytes()), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_padded() {
+        assert_eq!(padded_u32(b"  123"), Some(123));
+        assert_eq!(padded_u32(b"     "), None);
+        assert_eq!(padded_u32(b" 1 23"), None);
+        assert_eq!(padded_i32(b"-12345"), Some(-12345));
+        assert_eq!(padded_i32(b"+0"), Some(0));
+        assert_eq!(signed_i32(b"-4"), Some(-4));
+        assert_eq!(signed_i32(b"+4"), Some(4));
+        assert_eq!(signed_i32(b" 4"), None);
+        for s in [
+            " .00000950",
+            "-.00012345",
+            "15.49165514",
+            " 1.00270000",
+            "  0",
+            "-3.",
+        ] {
+            assert_eq!(padded_f64(s.as_bytes()), Some(s.trim().parse().unwrap()));
+        }
+        assert_eq!(padded_f64(b" ."), None);
+        assert_eq!(padded_f64(b"1.2.3"), None);
+        assert_eq!(epoch_day(b"045.18587073"), Some(45.18587073));
+        assert_eq!(epoch_day(b" 45.18587073"), None);
+    }
+}
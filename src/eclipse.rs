@@ -0,0 +1,395 @@
+//! Sun and Moon positions and eclipses of a satellite by the Earth.
+//!
+//! [`sun_position`] and [`moon_position`] are the low-precision analytical
+//! ephemerides of the Astronomical Almanac, as given by Vallado
+//! (*Fundamentals of Astrodynamics and Applications*, algorithms 29 and 31):
+//! about 0.01° for the Sun and 0.3° for the Moon. They are expressed in the
+//! equatorial frame of date, which for this accuracy is the same as the TEME
+//! frame of the SGP4 states.
+//!
+//! [`shadow`] tells whether a satellite is in the shadow of the Earth, and
+//! [`Propagator::eclipses`] finds the entries into and exits from the shadow
+//! along the propagated trajectory.
+
+use std::f64::consts::PI;
+
+use thiserror::Error;
+
+use crate::epoch::J2000;
+use crate::kepler::{dot, norm};
+use crate::{PropagationError, Propagator, EARTH_RADIUS};
+
+/// Astronomical unit in km.
+pub const AU: f64 = 149_597_870.7;
+
+/// Radius of the Sun in km.
+pub const SUN_RADIUS: f64 = 696_000.0;
+
+const DEG: f64 = PI / 180.0;
+
+/// Tolerance of the entry and exit times, in days (1 ms).
+const TOLERANCE: f64 = 1e-3 / 86_400.0;
+
+/// Julian centuries since J2000.
+fn centuries(jd: f64) -> f64 {
+    (jd - J2000) / 36525.0
+}
+
+/// Obliquity of the ecliptic (radians).
+fn obliquity(t: f64) -> f64 {
+    (23.439291 - 0.0130042 * t) * DEG
+}
+
+/// Geocentric position of the Sun (km) at the given Julian date.
+pub fn sun_position(jd: f64) -> [f64; 3] {
+    let t = centuries(jd);
+    let mean_longitude = 280.460 + 36000.771 * t;
+    let m = (357.5291092 + 35999.05034 * t) * DEG;
+    let longitude = (mean_longitude + 1.914666471 * m.sin() + 0.019994643 * (2.0 * m).sin()) * DEG;
+    let r = (1.000140612 - 0.016708617 * m.cos() - 0.000139589 * (2.0 * m).cos()) * AU;
+    let e = obliquity(t);
+    [
+        r * longitude.cos(),
+        r * e.cos() * longitude.sin(),
+        r * e.sin() * longitude.sin(),
+    ]
+}
+
+/// Geocentric position of the Moon (km) at the given Julian date.
+pub fn moon_position(jd: f64) -> [f64; 3] {
+    let t = centuries(jd);
+    let sin = |a: f64, b: f64| ((a + b * t) * DEG).sin();
+    let cos = |a: f64, b: f64| ((a + b * t) * DEG).cos();
+    let longitude = (218.32 + 481267.8813 * t + 6.29 * sin(134.9, 477198.85)
+        - 1.27 * sin(259.2, -413335.38)
+        + 0.66 * sin(235.7, 890534.23)
+        + 0.21 * sin(269.9, 954397.70)
+        - 0.19 * sin(357.5, 35999.05)
+        - 0.11 * sin(186.6, 966404.05))
+        * DEG;
+    let latitude = (5.13 * sin(93.3, 483202.03) + 0.28 * sin(228.2, 960400.87)
+        - 0.28 * sin(318.3, 6003.18)
+        - 0.17 * sin(217.6, -407332.20))
+        * DEG;
+    let parallax = (0.9508
+        + 0.0518 * cos(134.9, 477198.85)
+        + 0.0095 * cos(259.2, -413335.38)
+        + 0.0078 * cos(235.7, 890534.23)
+        + 0.0028 * cos(269.9, 954397.70))
+        * DEG;
+    let r = EARTH_RADIUS / parallax.sin();
+    let e = obliquity(t);
+    let (sin_lon, cos_lon) = longitude.sin_cos();
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    [
+        r * cos_lat * cos_lon,
+        r * (e.cos() * cos_lat * sin_lon - e.sin() * sin_lat),
+        r * (e.sin() * cos_lat * sin_lon + e.cos() * sin_lat),
+    ]
+}
+
+/// Model of the shadow of the Earth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShadowModel {
+    /// Cylinder of the radius of the Earth behind it: the Sun is a point at
+    /// infinity, and there is no penumbra.
+    Cylindrical,
+    /// Cones of the umbra and of the penumbra, from the apparent discs of the
+    /// Sun and of the Earth seen by the satellite.
+    #[default]
+    Conical,
+}
+
+/// Illumination of a satellite, from the darkest to the brightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Shadow {
+    /// The Sun is entirely hidden by the Earth.
+    Umbra,
+    /// The Sun is partly hidden by the Earth.
+    Penumbra,
+    /// The Sun is entirely visible.
+    Sunlit,
+}
+
+/// Illumination of a satellite at the given position, the Sun being at the
+/// given position (both in km, in the same geocentric frame).
+pub fn shadow(position: [f64; 3], sun: [f64; 3], model: ShadowModel) -> Shadow {
+    let r = norm(position);
+    match model {
+        ShadowModel::Cylindrical => {
+            let sun_dir = sun.map(|x| x / norm(sun));
+            let along = dot(position, sun_dir);
+            let across = (r * r - along * along).max(0.0).sqrt();
+            if along < 0.0 && across < EARTH_RADIUS {
+                Shadow::Umbra
+            } else {
+                Shadow::Sunlit
+            }
+        }
+        ShadowModel::Conical => {
+            let to_sun = [0, 1, 2].map(|k| sun[k] - position[k]);
+            let d = norm(to_sun);
+            // apparent radii of the Sun and of the Earth, and angle between their centers
+            let a = (SUN_RADIUS / d).asin();
+            let b = (EARTH_RADIUS / r).min(1.0).asin();
+            let c = (-dot(position, to_sun) / (r * d)).clamp(-1.0, 1.0).acos();
+            if c >= a + b {
+                Shadow::Sunlit
+            } else if c <= b - a {
+                Shadow::Umbra
+            } else {
+                Shadow::Penumbra
+            }
+        }
+    }
+}
+
+/// Passage of a satellite through the shadow of the Earth.
+///
+/// Times are Julian dates (UTC). A passage in progress at the start or at the
+/// end of the searched interval is cut at the bound of the interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eclipse {
+    /// Entry into the shadow (the penumbra with the conical model).
+    pub entry: f64,
+    /// Exit from the shadow.
+    pub exit: f64,
+    /// Entry into and exit from the umbra, if the satellite reaches it.
+    pub umbra: Option<(f64, f64)>,
+}
+
+/// Failure of an eclipse search.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EclipseError {
+    #[error("invalid time span, reason: {0}")]
+    InvalidTimeSpan(&'static str),
+    #[error(transparent)]
+    PropagationError(#[from] PropagationError),
+}
+
+impl Propagator {
+    /// Illumination of the satellite at the given Julian date (UTC).
+    pub fn shadow(&self, jd: f64, model: ShadowModel) -> Result<Shadow, PropagationError> {
+        let state = self.propagate_to(jd)?;
+        Ok(shadow(state.position, sun_position(jd), model))
+    }
+
+    /// Eclipses of the satellite between the given Julian dates (UTC).
+    ///
+    /// The illumination is sampled every `step` seconds and the entry and exit
+    /// times are refined by bisection to the millisecond. A shadow crossed in
+    /// less than a step may be missed: a minute is adequate for orbits above
+    /// the atmosphere.
+    ///
+    /// The bounds must be finite, `stop` must not precede `start`, and the
+    /// step must be long enough to advance the Julian dates.
+    pub fn eclipses(
+        &self,
+        start: f64,
+        stop: f64,
+        step: f64,
+        model: ShadowModel,
+    ) -> Result<Vec<Eclipse>, EclipseError> {
+        if !start.is_finite() || !stop.is_finite() {
+            return Err(EclipseError::InvalidTimeSpan(
+                "start and stop must be finite",
+            ));
+        }
+        if stop < start {
+            return Err(EclipseError::InvalidTimeSpan("stop precedes start"));
+        }
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(EclipseError::InvalidTimeSpan("step must be positive"));
+        }
+        let step = step / 86_400.0;
+        // the spacing of the dates is the widest at the bound of largest magnitude
+        if start + step == start || stop + step == stop {
+            return Err(EclipseError::InvalidTimeSpan("step is too short"));
+        }
+        let in_shadow = |shadow| shadow != Shadow::Sunlit;
+        let in_umbra = |shadow| shadow == Shadow::Umbra;
+
+        let mut eclipses = Vec::new();
+        let mut umbra: Option<(f64, f64)> = None;
+        let mut t0 = start;
+        let mut s0 = self.shadow(t0, model)?;
+        let mut entry = in_shadow(s0).then_some(t0);
+        if in_umbra(s0) {
+            umbra = Some((t0, stop));
+        }
+        while t0 < stop {
+            let t1 = (t0 + step).min(stop);
+            let s1 = self.shadow(t1, model)?;
+            // the umbra is inside the shadow: update it before closing an eclipse
+            if in_umbra(s0) != in_umbra(s1) {
+                let t = self.transition(t0, t1, model, in_umbra)?;
+                umbra = match umbra {
+                    None => Some((t, stop)),
+                    Some((entry, _)) => Some((entry, t)),
+                };
+            }
+            if in_shadow(s0) != in_shadow(s1) {
+                let t = self.transition(t0, t1, model, in_shadow)?;
+                match entry.take() {
+                    None => entry = Some(t),
+                    Some(entry) => eclipses.push(Eclipse {
+                        entry,
+                        exit: t,
+                        umbra: umbra.take(),
+                    }),
+                }
+            }
+            (t0, s0) = (t1, s1);
+        }
+        if let Some(entry) = entry {
+            eclipses.push(Eclipse {
+                entry,
+                exit: stop,
+                umbra,
+            });
+        }
+        Ok(eclipses)
+    }
+
+    /// Time at which the predicate changes between `t0` and `t1`.
+    fn transition(
+        &self,
+        mut t0: f64,
+        mut t1: f64,
+        model: ShadowModel,
+        predicate: impl Fn(Shadow) -> bool,
+    ) -> Result<f64, PropagationError> {
+        let p0 = predicate(self.shadow(t0, model)?);
+        while t1 - t0 > TOLERANCE {
+            let t = 0.5 * (t0 + t1);
+            // the dates are too coarse to reach the tolerance
+            if t <= t0 || t >= t1 {
+                break;
+            }
+            if predicate(self.shadow(t, model)?) == p0 {
+                t0 = t;
+            } else {
+                t1 = t;
+            }
+        }
+        Ok(0.5 * (t0 + t1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    fn assert_close(a: [f64; 3], b: [f64; 3], tolerance: f64) {
+        let d = [0, 1, 2].map(|k| a[k] - b[k]);
+        assert!(norm(d) < tolerance, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_ephemerides() {
+        // Vallado, examples 5-1 and 5-3
+        let sun = [0.9771945, 0.1924424, 0.0834308].map(|x| x * AU);
+        assert_close(sun_position(2_453_827.5), sun, 1e-5 * AU);
+        let moon = [-134_240.626, -311_571.590, -126_693.785];
+        assert_close(moon_position(2_449_470.5), moon, 10.0);
+    }
+
+    #[test]
+    fn test_shadow() {
+        let sun = [AU, 0.0, 0.0];
+        let shadow = |x, y, model| shadow([x, y, 0.0], sun, model);
+        for model in [ShadowModel::Cylindrical, ShadowModel::Conical] {
+            assert_eq!(shadow(7000.0, 0.0, model), Shadow::Sunlit);
+            assert_eq!(shadow(0.0, 7000.0, model), Shadow::Sunlit);
+            assert_eq!(shadow(-7000.0, 0.0, model), Shadow::Umbra);
+        }
+        // the umbra narrows and the penumbra widens behind the Earth
+        let edge = EARTH_RADIUS - 10.0;
+        assert_eq!(
+            shadow(-42_000.0, edge, ShadowModel::Cylindrical),
+            Shadow::Umbra
+        );
+        assert_eq!(
+            shadow(-42_000.0, edge, ShadowModel::Conical),
+            Shadow::Penumbra
+        );
+        let edge = EARTH_RADIUS + 10.0;
+        assert_eq!(
+            shadow(-42_000.0, edge, ShadowModel::Cylindrical),
+            Shadow::Sunlit
+        );
+        assert_eq!(
+            shadow(-42_000.0, edge, ShadowModel::Conical),
+            Shadow::Penumbra
+        );
+    }
+
+    #[test]
+    fn test_eclipses() {
+        let propagator = crate::parse(ISS).unwrap().propagator().unwrap();
+        let start = propagator.epoch_julian_date();
+        let stop = start + 1.0;
+
+        let eclipses = propagator
+            .eclipses(start, stop, 60.0, ShadowModel::Conical)
+            .unwrap();
+        assert!((15..=17).contains(&eclipses.len()));
+        for eclipse in &eclipses {
+            let (entry, exit) = eclipse.umbra.unwrap();
+            assert!(eclipse.entry <= entry && entry < exit && exit <= eclipse.exit);
+            if start < eclipse.entry && eclipse.exit < stop {
+                let minutes = (eclipse.exit - eclipse.entry) * 1440.0;
+                assert!((15.0..40.0).contains(&minutes));
+                let penumbra = (entry - eclipse.entry) * 86_400.0;
+                assert!((1.0..60.0).contains(&penumbra));
+                let t = 0.5 * (entry + exit);
+                assert_eq!(
+                    propagator.shadow(t, ShadowModel::Conical),
+                    Ok(Shadow::Umbra)
+                );
+            }
+        }
+
+        // the cylindrical shadow lies between the conical umbra and penumbra
+        let cylindrical = propagator
+            .eclipses(start, stop, 60.0, ShadowModel::Cylindrical)
+            .unwrap();
+        assert_eq!(cylindrical.len(), eclipses.len());
+        let complete =
+            |(cylinder, _): &(&Eclipse, &Eclipse)| start < cylinder.entry && cylinder.exit < stop;
+        for (cylinder, cone) in cylindrical.iter().zip(&eclipses).filter(complete) {
+            assert_eq!(cylinder.umbra, Some((cylinder.entry, cylinder.exit)));
+            let umbra = cone.umbra.unwrap();
+            assert!(cone.entry < cylinder.entry && cylinder.entry < umbra.0);
+            assert!(umbra.1 < cylinder.exit && cylinder.exit < cone.exit);
+        }
+    }
+
+    #[test]
+    fn test_invalid_time_span() {
+        let propagator = crate::parse(ISS).unwrap().propagator().unwrap();
+        let start = propagator.epoch_julian_date();
+        let eclipses =
+            |start, stop, step| propagator.eclipses(start, stop, step, ShadowModel::Conical);
+        for (stop, step) in [
+            (start + 1.0, 0.0),
+            (start + 1.0, -60.0),
+            (start + 1.0, f64::NAN),
+            (start + 1.0, f64::INFINITY),
+            (start + 1.0, 1e-6),
+            (f64::INFINITY, 60.0),
+            (f64::NAN, 60.0),
+            (start - 1.0, 60.0),
+        ] {
+            assert!(matches!(
+                eclipses(start, stop, step),
+                Err(EclipseError::InvalidTimeSpan(_))
+            ));
+        }
+        assert_eq!(eclipses(start, start, 60.0), Ok(vec![]));
+    }
+}
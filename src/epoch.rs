@@ -0,0 +1,141 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::math::round;
+use crate::TLE;
+
+/// Julian date of the J2000 reference epoch (1 January 2000, 12:00).
+pub const J2000: f64 = 2_451_545.0;
+
+/// Number of minutes in a day.
+pub(crate) const MINUTES_PER_DAY: f64 = 1440.0;
+
+/// Full year corresponding to a two-digit year of a TLE (1957–2056).
+pub(crate) fn full_year(y: u8) -> i32 {
+    if y <= 56 {
+        2000 + y as i32
+    } else {
+        1900 + y as i32
+    }
+}
+
+/// Whether the year is a leap year in the Gregorian calendar.
+pub(crate) fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days since 1970-01-01 of the given proleptic Gregorian date.
+pub(crate) fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Proleptic Gregorian date (year, month, day) of the given number of days since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400) as i32 + (month <= 2) as i32;
+    (year, month, day)
+}
+
+/// Format a Julian date (UTC) as an ISO 8601 timestamp with millisecond precision.
+pub(crate) fn iso8601(jd: f64) -> String {
+    let ms = round((jd - 2_440_587.5) * 86_400_000.0) as i64;
+    let (days, ms) = (ms.div_euclid(86_400_000), ms.rem_euclid(86_400_000));
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Julian date of the given year and day of the year (starting from `1.0` at midnight of 1 January).
+pub(crate) fn julian_date(year: i32, day: f64) -> f64 {
+    days_from_civil(year, 1, 1) as f64 + 2_440_587.5 + (day - 1.0)
+}
+
+/// Year and day of the year (starting from `1.0`) of the given Julian date,
+/// if it is in the years 1957 to 2056 which a TLE can represent.
+#[cfg(feature = "sgp4")]
+pub(crate) fn year_and_day(jd: f64) -> Option<(i32, f64)> {
+    // also rejects NaN
+    if !(julian_date(1957, 1.0)..julian_date(2057, 1.0)).contains(&jd) {
+        return None;
+    }
+    let days = jd - 2_440_587.5;
+    let mut year = 1970 + (days / 365.2425).floor() as i32;
+    loop {
+        let start = days_from_civil(year, 1, 1) as f64;
+        if days < start {
+            year -= 1;
+        } else if days >= days_from_civil(year + 1, 1, 1) as f64 {
+            year += 1;
+        } else {
+            return Some((year, days - start + 1.0));
+        }
+    }
+}
+
+impl TLE {
+    /// Julian date (UTC) of the epoch.
+    pub fn epoch_julian_date(&self) -> f64 {
+        julian_date(self.epoch_year, self.epoch_day)
+    }
+
+    /// Years elapsed since J2000 at the epoch, as expected by the SGP4 propagator.
+    pub fn epoch_j2000_years(&self) -> f64 {
+        (self.epoch_julian_date() - J2000) / 365.25
+    }
+
+    /// Epoch formatted as an ISO 8601 timestamp (UTC) with millisecond precision.
+    pub fn epoch_iso8601(&self) -> String {
+        iso8601(self.epoch_julian_date())
+    }
+
+    /// Minutes elapsed from the epoch to the given Julian date (UTC).
+    pub fn minutes_since_epoch(&self, jd: f64) -> f64 {
+        (jd - self.epoch_julian_date()) * MINUTES_PER_DAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_round_trip() {
+        // every day over four years around 1970, and a sample beyond
+        let sampled = (-800_000..800_000).step_by(997);
+        for days in (-730..730).chain(sampled) {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn test_julian_date() {
+        assert_eq!(julian_date(2000, 1.5), J2000);
+        assert_eq!(iso8601(J2000), "2000-01-01T12:00:00.000");
+        assert_eq!(
+            iso8601(julian_date(2020, 45.18587073)),
+            "2020-02-14T04:27:39.231"
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use alloc::format;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::format::checksum;
+use crate::math::scale10;
+use crate::{from_lines, Classification, EphemerisType, Error, IntDesigField, TLE};
+
+/// Number in the decimal-point-assumed exponential notation of the `ddn_o6`
+/// and `bstar` columns (e.g. ` 25302-4`, i.e. `0.25302 × 10^-4`).
+///
+/// The sign of a zero exponent is preserved, since both ` 00000-0` and
+/// ` 00000+0` are found in distributed TLEs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpDecimal {
+    pub(crate) mantissa: i32,
+    pub(crate) exponent: i8,
+    pub(crate) negative_exponent: bool,
+}
+
+impl ExpDecimal {
+    /// Create the number `mantissa × 10^(exponent - 5)`.
+    ///
+    /// The mantissa must have at most five digits and the exponent at most one.
+    pub fn new(mantissa: i32, exponent: i8) -> Result<ExpDecimal, Error> {
+        if !(-99_999..=99_999).contains(&mantissa) || !(-9..=9).contains(&exponent) {
+            return Err(Error::ParseError("exponential notation"));
+        }
+        Ok(ExpDecimal {
+            mantissa,
+            exponent,
+            negative_exponent: exponent < 0 || (exponent == 0 && mantissa == 0),
+        })
+    }
+
+    /// Parse the eight columns of the field.
+    fn parse(s: &[u8]) -> Result<ExpDecimal, Error> {
+        let mantissa = core::str::from_utf8(&s[..6])?.trim().parse::<i32>()?;
+        let (sign, digit) = (s[6], s[7]);
+        if !matches!(sign, b'+' | b'-') || !digit.is_ascii_digit() {
+            return Err(Error::ParseError("exponential notation"));
+        }
+        let negative_exponent = sign == b'-';
+        let exponent = (digit - b'0') as i8;
+        Ok(ExpDecimal {
+            mantissa,
+            exponent: if negative_exponent {
+                -exponent
+            } else {
+                exponent
+            },
+            negative_exponent,
+        })
+    }
+
+    /// Signed mantissa (five digits).
+    pub fn mantissa(self) -> i32 {
+        self.mantissa
+    }
+
+    /// Exponent (one digit).
+    pub fn exponent(self) -> i8 {
+        self.exponent
+    }
+
+    /// Value of the number.
+    pub fn to_f64(self) -> f64 {
+        scale10(self.mantissa as f64, self.exponent as i32 - 5)
+    }
+}
+
+/// Formats the number as in a TLE (e.g. ` 25302-4`).
+impl fmt::Display for ExpDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{:05}{}{}",
+            if self.mantissa < 0 { '-' } else { ' ' },
+            self.mantissa.unsigned_abs(),
+            if self.negative_exponent { '-' } else { '+' },
+            self.exponent.unsigned_abs()
+        )
+    }
+}
+
+/// TLE whose numeric fields are stored exactly, as scaled integers.
+///
+/// Parsing and formatting an `ExactTLE` is lossless: lines with a correct
+/// checksum and the standard layout (as produced by [`TLE::line1`] and
+/// [`TLE::line2`]) are reproduced exactly. Unlike [`TLE`], it implements
+/// `Eq` and `Hash`, comparing the element sets digit by digit.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExactTLE {
+    /// Name of the object.
+    pub name: String,
+    /// Norad number.
+    pub norad: String,
+    /// Classification.
+    pub classification: Classification,
+    /// International designator, if any.
+    pub int_desig: IntDesigField,
+    /// Year of the epoch.
+    pub epoch_year: i32,
+    /// Day of the year plus fraction of the day, in units of 10⁻⁸ day.
+    pub epoch_day: u64,
+    /// First time-derivative of the mean motion divided by 2, in units of 10⁻⁸ rev/day².
+    pub dn_o2: i64,
+    /// Second time-derivative of the mean motion divided by 6.
+    pub ddn_o6: ExpDecimal,
+    /// BSTAR coefficient.
+    pub bstar: ExpDecimal,
+    /// Ephemeris type (orbital model).
+    pub ephem_type: EphemerisType,
+    /// Element set number.
+    pub set_num: u32,
+    /// Inclination, in units of 10⁻⁴ degree.
+    pub inc: u32,
+    /// Right ascension of the ascending node, in units of 10⁻⁴ degree.
+    pub raan: u32,
+    /// Eccentricity, in units of 10⁻⁷.
+    pub ecc: u32,
+    /// Argument of perigee, in units of 10⁻⁴ degree.
+    pub argp: u32,
+    /// Mean anomaly, in units of 10⁻⁴ degree.
+    pub M: u32,
+    /// Mean motion, in units of 10⁻⁸ rev/day.
+    pub n: u64,
+    /// Revolution number.
+    pub rev_num: u32,
+}
+
+/// Parse a fixed-point number into an integer in units of `10^-decimals`.
+fn parse_fixed(s: &[u8], decimals: u32) -> Result<i64, Error> {
+    let s = core::str::from_utf8(s)?.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if (int.is_empty() && frac.is_empty())
+        || frac.len() > decimals as usize
+        || !int.bytes().chain(frac.bytes()).all(|c| c.is_ascii_digit())
+    {
+        return Err(Error::ParseError("fixed-point number"));
+    }
+    let digits = |s: &str| s.bytes().fold(0i64, |n, c| n * 10 + (c - b'0') as i64);
+    let value =
+        digits(int) * 10i64.pow(decimals) + digits(frac) * 10i64.pow(decimals - frac.len() as u32);
+    Ok(if negative { -value } else { value })
+}
+
+fn parse_unsigned<T: TryFrom<i64>>(s: &[u8], decimals: u32) -> Result<T, Error> {
+    T::try_from(parse_fixed(s, decimals)?).map_err(|_| Error::ParseError("fixed-point number"))
+}
+
+/// Format a non-negative integer in units of `10^-decimals` as a fixed-point number.
+fn format_fixed(value: u64, decimals: u32) -> String {
+    let scale = 10u64.pow(decimals);
+    format!(
+        "{}.{:0width$}",
+        value / scale,
+        value % scale,
+        width = decimals as usize
+    )
+}
+
+impl ExactTLE {
+    /// Parse a TLE from the three individual lines, as [`from_lines`].
+    pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<ExactTLE, Error> {
+        let tle = from_lines(name, line1, line2)?;
+        let (line1, line2) = (line1.as_bytes(), line2.as_bytes());
+        Ok(ExactTLE {
+            name: tle.name,
+            norad: tle.norad,
+            classification: tle.classification,
+            int_desig: tle.int_desig,
+            epoch_year: tle.epoch_year,
+            epoch_day: parse_unsigned(&line1[20..=31], 8)?,
+            dn_o2: parse_fixed(&line1[33..=42], 8)?,
+            ddn_o6: ExpDecimal::parse(&line1[44..=51])?,
+            bstar: ExpDecimal::parse(&line1[53..=60])?,
+            ephem_type: tle.ephem_type,
+            set_num: tle.set_num,
+            inc: parse_unsigned(&line2[8..=15], 4)?,
+            raan: parse_unsigned(&line2[17..=24], 4)?,
+            ecc: parse_unsigned(&line2[26..=32], 0)?,
+            argp: parse_unsigned(&line2[34..=41], 4)?,
+            M: parse_unsigned(&line2[43..=50], 4)?,
+            n: parse_unsigned(&line2[52..=62], 8)?,
+            rev_num: tle.rev_num,
+        })
+    }
+
+    /// Format the first line of the TLE, including the checksum.
+    pub fn line1(&self) -> String {
+        let dn_o2 = format_fixed(self.dn_o2.unsigned_abs(), 8);
+        let mut line = format!(
+            "1 {:>5}{} {:<8} {:02}{:0>12} {}{} {} {} {} {:>4}",
+            self.norad,
+            self.classification,
+            self.int_desig,
+            self.epoch_year.rem_euclid(100),
+            format_fixed(self.epoch_day, 8),
+            if self.dn_o2 < 0 { '-' } else { ' ' },
+            dn_o2.strip_prefix('0').unwrap_or(&dn_o2),
+            self.ddn_o6,
+            self.bstar,
+            self.ephem_type,
+            self.set_num,
+        );
+        let c = checksum(line.as_bytes());
+        line.push((b'0' + c) as char);
+        line
+    }
+
+    /// Format the second line of the TLE, including the checksum.
+    pub fn line2(&self) -> String {
+        let mut line = format!(
+            "2 {:>5} {:>8} {:>8} {:07} {:>8} {:>8} {:>11}{:>5}",
+            self.norad,
+            format_fixed(self.inc as u64, 4),
+            format_fixed(self.raan as u64, 4),
+            self.ecc,
+            format_fixed(self.argp as u64, 4),
+            format_fixed(self.M as u64, 4),
+            format_fixed(self.n, 8),
+            self.rev_num,
+        );
+        let c = checksum(line.as_bytes());
+        line.push((b'0' + c) as char);
+        line
+    }
+
+    /// Convert the fields to `f64`, as they would be parsed by [`from_lines`].
+    pub fn to_tle(&self) -> TLE {
+        let fixed = |value: i64, decimals: i32| scale10(value as f64, -decimals);
+        TLE {
+            name: self.name.clone(),
+            norad: self.norad.clone(),
+            classification: self.classification,
+            int_desig: self.int_desig.clone(),
+            epoch_year: self.epoch_year,
+            epoch_day: fixed(self.epoch_day as i64, 8),
+            dn_o2: fixed(self.dn_o2, 8),
+            ddn_o6: self.ddn_o6.to_f64(),
+            bstar: self.bstar.to_f64(),
+            ephem_type: self.ephem_type,
+            set_num: self.set_num,
+            inc: fixed(self.inc as i64, 4),
+            raan: fixed(self.raan as i64, 4),
+            ecc: fixed(self.ecc as i64, 7),
+            argp: fixed(self.argp as i64, 4),
+            M: fixed(self.M as i64, 4),
+            n: fixed(self.n as i64, 8),
+            rev_num: self.rev_num,
+        }
+    }
+}
+
+/// Rounds the fields to the precision of the TLE format.
+impl TryFrom<&TLE> for ExactTLE {
+    type Error = Error;
+
+    fn try_from(tle: &TLE) -> Result<ExactTLE, Error> {
+        ExactTLE::from_lines(&tle.name, &tle.line1(), &tle.line2())
+    }
+}
+
+/// Formats the TLE as three lines (name, line 1 and line 2) separated by `\n`.
+impl fmt::Display for ExactTLE {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}\n{}", self.name, self.line1(), self.line2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const RECORDS: [[&str; 3]; 3] = [
+        [
+            "ISS (ZARYA)",
+            "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990",
+            "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791",
+        ],
+        [
+            "VANGUARD 1",
+            "1 00005U 58002B   20045.59766236  .00000028  00000+0  39591-4 0  9995",
+            "2 00005  34.2467 209.5404 1846039 214.0436 132.2098 10.84868778205423",
+        ],
+        [
+            "DECAYING",
+            "1 99999U          20001.00000000 -.00012345 -12345-6 -10000+1 0  9998",
+            "2 99999 180.0000 359.9999 9999999   0.0000   0.0001  0.00000001    04",
+        ],
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        for [name, line1, line2] in RECORDS {
+            let exact = ExactTLE::from_lines(name, line1, line2).unwrap();
+            assert_eq!(exact.line1(), line1);
+            assert_eq!(exact.line2(), line2);
+            assert_eq!(exact.to_tle(), from_lines(name, line1, line2).unwrap());
+            // the sign of a zero exponent is lost in the conversion to `f64`
+            let tle = exact.to_tle();
+            assert_eq!(ExactTLE::try_from(&tle).unwrap().to_tle(), tle);
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let [name, line1, line2] = RECORDS[0];
+        let exact = ExactTLE::from_lines(name, line1, line2).unwrap();
+        assert_eq!(exact.epoch_day, 4_518_587_073);
+        assert_eq!(exact.dn_o2, 950);
+        assert_eq!(
+            (exact.bstar.mantissa(), exact.bstar.exponent()),
+            (25302, -4)
+        );
+        assert_eq!(exact.inc, 516_443);
+        assert_eq!(exact.ecc, 4885);
+        assert_eq!(exact.n, 1_549_165_514);
+        assert_eq!(ExpDecimal::new(0, 0).unwrap().to_string(), " 00000-0");
+        assert!(ExpDecimal::new(100_000, 0).is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        let set: HashSet<ExactTLE> = RECORDS
+            .iter()
+            .chain(RECORDS.iter())
+            .map(|[name, line1, line2]| ExactTLE::from_lines(name, line1, line2).unwrap())
+            .collect();
+        assert_eq!(set.len(), RECORDS.len());
+    }
+}
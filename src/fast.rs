@@ -0,0 +1,268 @@
+//! Fast decoder of element lines in the canonical layout.
+//!
+//! The lines written by the catalogs always have the same layout: the fixed
+//! columns (spaces, decimal points, line numbers) are at the same position
+//! and most numeric fields are zero-padded. This decoder checks all the fixed
+//! columns of a line in one pass, eight bytes at a time, and parses the
+//! 8-digit fraction fields with SWAR (SIMD within a register) arithmetic.
+//!
+//! Lines that do not have the canonical layout, including malformed ones, are
+//! handed over to [`crate::from_line_bytes`]: the result, TLE or error, is
+//! always the same.
+
+use alloc::string::String;
+
+use crate::digits;
+use crate::epoch::full_year;
+use crate::math::scale10;
+use crate::name::{decode_name, normalize_name};
+use crate::{Classification, EphemerisType, Error, IntDesig, TLE};
+
+/// Number of 8-byte words covering a line.
+const WORDS: usize = 9;
+
+/// Expected values of the fixed columns of a line, and the mask selecting them.
+struct Template {
+    expected: [u64; WORDS],
+    mask: [u64; WORDS],
+}
+
+impl Template {
+    const fn new(fixed: &[(usize, u8)]) -> Template {
+        let mut template = Template {
+            expected: [0; WORDS],
+            mask: [0; WORDS],
+        };
+        let mut i = 0;
+        while i < fixed.len() {
+            let (col, c) = fixed[i];
+            let shift = 8 * (col % 8);
+            template.expected[col / 8] |= (c as u64) << shift;
+            template.mask[col / 8] |= 0xff << shift;
+            i += 1;
+        }
+        template
+    }
+
+    /// Whether the line is ASCII and has the expected fixed columns.
+    fn matches(&self, words: &[u64; WORDS]) -> bool {
+        let mut diff = 0;
+        let mut high = 0;
+        for ((word, expected), mask) in words.iter().zip(&self.expected).zip(&self.mask) {
+            diff |= (word ^ expected) & mask;
+            high |= word;
+        }
+        diff == 0 && high & 0x8080_8080_8080_8080 == 0
+    }
+}
+
+const LINE1: Template = Template::new(&[
+    (0, b'1'),
+    (1, b' '),
+    (8, b' '),
+    (17, b' '),
+    (23, b'.'),
+    (32, b' '),
+    (34, b'.'),
+    (43, b' '),
+    (52, b' '),
+    (61, b' '),
+    (63, b' '),
+]);
+
+const LINE2: Template = Template::new(&[
+    (0, b'2'),
+    (1, b' '),
+    (7, b' '),
+    (11, b'.'),
+    (16, b' '),
+    (20, b'.'),
+    (25, b' '),
+    (33, b' '),
+    (37, b'.'),
+    (42, b' '),
+    (46, b'.'),
+    (51, b' '),
+    (54, b'.'),
+]);
+
+/// The 69 columns of a line, optionally followed by `\n`, as little-endian words.
+fn words(line: &[u8]) -> Option<[u64; WORDS]> {
+    let line = match line.len() {
+        69 => line,
+        70 if line[69] == b'\n' => &line[..69],
+        _ => return None,
+    };
+    let mut bytes = [0; 8 * WORDS];
+    bytes[..69].copy_from_slice(line);
+    let mut words = [0; WORDS];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Some(words)
+}
+
+/// The eight bytes starting at the given column, as a little-endian word.
+fn word_at(line: &[u8], col: usize) -> u64 {
+    u64::from_le_bytes(line[col..col + 8].try_into().unwrap())
+}
+
+/// Whether the eight bytes of the word are all ASCII digits.
+fn is_eight_digits(v: u64) -> bool {
+    (v & 0xf0f0_f0f0_f0f0_f0f0)
+        | ((v.wrapping_add(0x0606_0606_0606_0606) & 0xf0f0_f0f0_f0f0_f0f0) >> 4)
+        == 0x3333_3333_3333_3333
+}
+
+/// Value of eight ASCII digits, the first one being the least significant byte of the word.
+fn eight_digits(v: u64) -> Option<u32> {
+    if !is_eight_digits(v) {
+        return None;
+    }
+    let v = v - 0x3030_3030_3030_3030;
+    // combine the digits pairwise, then the pairs into the two halves
+    let v = v.wrapping_mul(10) + (v >> 8);
+    let v = ((v & 0x0000_00ff_0000_00ff).wrapping_mul(100 + (1_000_000 << 32))
+        + ((v >> 16) & 0x0000_00ff_0000_00ff).wrapping_mul(1 + (10_000 << 32)))
+        >> 32;
+    Some(v as u32)
+}
+
+/// Sign of a field, from a column which is a space or `+` for positive values.
+fn sign(c: u8) -> Option<bool> {
+    match c {
+        b' ' | b'+' => Some(false),
+        b'-' => Some(true),
+        _ => None,
+    }
+}
+
+/// Mantissa and exponent in the decimal-point-assumed notation, such as ` 25302-4`.
+fn exp_decimal(s: &[u8]) -> Option<f64> {
+    let negative = sign(s[0])?;
+    let m = digits::u32_5_digits(&s[1..6])? as i32;
+    let e = match s[6] {
+        b'+' => (s[7] as char).to_digit(10)? as i32,
+        b'-' => -((s[7] as char).to_digit(10)? as i32),
+        _ => return None,
+    };
+    Some(scale10((if negative { -m } else { m }) as f64, e - 5))
+}
+
+/// Decode the lines if they have the canonical layout.
+fn decode(name: &[u8], line1: &[u8], line2: &[u8]) -> Option<TLE> {
+    if !(LINE1.matches(&words(line1)?) && LINE2.matches(&words(line2)?)) {
+        return None;
+    }
+
+    let norad: String = line1[2..=6]
+        .trim_ascii()
+        .iter()
+        .map(|&c| char::from(c))
+        .collect();
+    if line2[2..=6] != *norad.as_bytes() {
+        return None;
+    }
+    let classification = Classification::try_from(line1[7] as char).ok()?;
+    let int_desig = IntDesig::from_tle_columns(&line1[9..=16]).ok()?.into();
+    let epoch_year = full_year(digits::u8_2_digits(&line1[18..=19])?);
+    let day = digits::u16_3_digits(&line1[20..=22])?;
+    let fraction = eight_digits(word_at(line1, 24))?;
+    let epoch_day = (day as u64 * 100_000_000 + fraction as u64) as f64 / 1e8;
+    let dn_o2 = eight_digits(word_at(line1, 35))? as f64 / 1e8;
+    let dn_o2 = if sign(line1[33])? { -dn_o2 } else { dn_o2 };
+    let ddn_o6 = exp_decimal(&line1[44..=51])?;
+    let bstar = exp_decimal(&line1[53..=60])?;
+    let ephem_type = EphemerisType::try_from(line1[62] as char).ok()?;
+    let set_num = digits::padded_u32(&line1[64..=67])?;
+
+    let inc = digits::f64_3_4_padded(&line2[8..=15])?;
+    let raan = digits::f64_3_4_padded(&line2[17..=24])?;
+    // the space of column 25 stands in for a leading zero
+    let ecc = eight_digits(word_at(line2, 25) & !0xff | b'0' as u64)? as f64 / 1e7;
+    let argp = digits::f64_3_4_padded(&line2[34..=41])?;
+    #[allow(non_snake_case)]
+    let M = digits::f64_3_4_padded(&line2[43..=50])?;
+    let revs = match line2[52] {
+        b' ' => 0,
+        c => (c as char).to_digit(10)? as u64,
+    } * 10
+        + (line2[53] as char).to_digit(10)? as u64;
+    let n = (revs * 100_000_000 + eight_digits(word_at(line2, 55))? as u64) as f64 / 1e8;
+    let rev_num = digits::padded_u32(&line2[63..=67])?;
+
+    Some(TLE {
+        name: normalize_name(&decode_name(name)),
+        norad,
+        classification,
+        int_desig,
+        epoch_year,
+        epoch_day,
+        dn_o2,
+        ddn_o6,
+        bstar,
+        ephem_type,
+        set_num,
+        inc,
+        raan,
+        ecc,
+        argp,
+        M,
+        n,
+        rev_num,
+    })
+}
+
+/// Parse a TLE from the three individual lines, like [`crate::from_lines`].
+pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
+    from_line_bytes(name.as_bytes(), line1.as_bytes(), line2.as_bytes())
+}
+
+/// Parse a TLE from the three individual lines given as bytes, like [`crate::from_line_bytes`].
+pub fn from_line_bytes(name: &[u8], line1: &[u8], line2: &[u8]) -> Result<TLE, Error> {
+    match decode(name, line1, line2) {
+        Some(tle) => Ok(tle),
+        None => crate::from_line_bytes(name, line1, line2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990";
+    const LINE2: &str = "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_eight_digits() {
+        for n in (0..100_000_000).step_by(9_973).chain([99_999_999]) {
+            let s = format!("{:08}", n);
+            assert_eq!(eight_digits(word_at(s.as_bytes(), 0)), Some(n));
+        }
+        for s in ["1234567 ", "/2345678", "12:45678", "12345.78"] {
+            assert_eq!(eight_digits(word_at(s.as_bytes(), 0)), None);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let tle = decode(b"ISS (ZARYA)", LINE1.as_bytes(), LINE2.as_bytes()).unwrap();
+        assert_eq!(tle, crate::from_lines("ISS (ZARYA)", LINE1, LINE2).unwrap());
+
+        // non-canonical but valid
+        let line1 = LINE1.replacen(" .00000950", " 0.0000095", 1);
+        assert!(decode(b"", line1.as_bytes(), LINE2.as_bytes()).is_none());
+        assert_eq!(
+            from_lines("", &line1, LINE2),
+            crate::from_lines("", &line1, LINE2)
+        );
+        assert!(from_lines("", &line1, LINE2).is_ok());
+
+        // invalid
+        let line1 = LINE1.replacen("U", "Ü", 1);
+        assert_eq!(
+            from_lines("", &line1[..69], LINE2),
+            Err(Error::NonAsciiLine(1))
+        );
+    }
+}
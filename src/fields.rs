@@ -0,0 +1,135 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::Error;
+
+/// Security classification of the element set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Classification {
+    /// `U`: unclassified.
+    #[default]
+    Unclassified,
+    /// `C`: classified.
+    Classified,
+    /// `S`: secret.
+    Secret,
+}
+
+impl Classification {
+    /// Character representing the classification in a TLE.
+    pub fn code(self) -> char {
+        match self {
+            Classification::Unclassified => 'U',
+            Classification::Classified => 'C',
+            Classification::Secret => 'S',
+        }
+    }
+}
+
+impl TryFrom<char> for Classification {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Classification, Error> {
+        match c {
+            'U' => Ok(Classification::Unclassified),
+            'C' => Ok(Classification::Classified),
+            'S' => Ok(Classification::Secret),
+            _ => Err(Error::InvalidClassification(c)),
+        }
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Orbital model used to generate the element set.
+///
+/// Distributed TLEs always use `0`, meaning that the elements are meant for SGP4/SDP4.
+/// The other digits and the blank are accepted and preserved, since they appear in
+/// some historical and non-standard data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EphemerisType {
+    /// `0`: default, i.e. SGP4/SDP4.
+    #[default]
+    Default,
+    /// `1`: SGP.
+    Sgp,
+    /// `2`: SGP4.
+    Sgp4,
+    /// `3`: SDP4.
+    Sdp4,
+    /// `4`: SGP8.
+    Sgp8,
+    /// `5`: SDP8.
+    Sdp8,
+    /// Any other digit or a blank.
+    Unknown(char),
+}
+
+impl EphemerisType {
+    /// Character representing the ephemeris type in a TLE.
+    pub fn code(self) -> char {
+        match self {
+            EphemerisType::Default => '0',
+            EphemerisType::Sgp => '1',
+            EphemerisType::Sgp4 => '2',
+            EphemerisType::Sdp4 => '3',
+            EphemerisType::Sgp8 => '4',
+            EphemerisType::Sdp8 => '5',
+            EphemerisType::Unknown(c) => c,
+        }
+    }
+}
+
+impl TryFrom<char> for EphemerisType {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<EphemerisType, Error> {
+        match c {
+            '0' => Ok(EphemerisType::Default),
+            '1' => Ok(EphemerisType::Sgp),
+            '2' => Ok(EphemerisType::Sgp4),
+            '3' => Ok(EphemerisType::Sdp4),
+            '4' => Ok(EphemerisType::Sgp8),
+            '5' => Ok(EphemerisType::Sdp8),
+            '6'..='9' | ' ' => Ok(EphemerisType::Unknown(c)),
+            _ => Err(Error::InvalidEphemerisType(c)),
+        }
+    }
+}
+
+impl fmt::Display for EphemerisType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for c in "UCS".chars() {
+            assert_eq!(Classification::try_from(c).unwrap().code(), c);
+        }
+        for c in "0123456789 ".chars() {
+            assert_eq!(EphemerisType::try_from(c).unwrap().code(), c);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            Classification::try_from('X'),
+            Err(Error::InvalidClassification('X'))
+        );
+        assert_eq!(
+            EphemerisType::try_from('A'),
+            Err(Error::InvalidEphemerisType('A'))
+        );
+    }
+}
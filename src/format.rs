@@ -0,0 +1,659 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use thiserror::Error;
+
+use crate::math::{round, scale10};
+use crate::{IntDesigField, TLE};
+
+/// Layout of the records of a TLE file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+    /// Three lines per record, the first one being the name of the object (3LE).
+    #[default]
+    ThreeLine,
+    /// Three lines per record, the name being prefixed by `0 ` (Space-Track "3le").
+    SpaceTrack,
+    /// Three lines per record, the name being padded with spaces to 24 characters (CelesTrak).
+    CelesTrak,
+    /// Two lines per record, without name (2LE).
+    TwoLine,
+}
+
+/// Length of the names in the CelesTrak format.
+const CELESTRAK_NAME_LEN: usize = 24;
+
+/// Whether the line looks like the first line of a TLE.
+pub(crate) fn is_line1(line: &[u8]) -> bool {
+    line.starts_with(b"1 ") && line.trim_ascii_end().len() == 69
+}
+
+/// Whether the line looks like the second line of a TLE.
+#[cfg(feature = "std")]
+pub(crate) fn is_line2(line: &[u8]) -> bool {
+    line.starts_with(b"2 ") && line.trim_ascii_end().len() == 69
+}
+
+impl Format {
+    /// Recognise the format of a record from its name line (`None` if the record has no name).
+    pub fn detect(name_line: Option<&str>) -> Format {
+        let line = match name_line {
+            None => return Format::TwoLine,
+            Some(line) => line.trim_end_matches(['\r', '\n']),
+        };
+        if line.starts_with("0 ") {
+            Format::SpaceTrack
+        } else if line.len() == CELESTRAK_NAME_LEN && line.ends_with(' ') {
+            Format::CelesTrak
+        } else {
+            Format::ThreeLine
+        }
+    }
+
+    /// Whether the records of this format have a name line.
+    pub fn has_name(self) -> bool {
+        self != Format::TwoLine
+    }
+
+    /// Extract the name of the object from a name line of this format.
+    pub fn name(self, name_line: &str) -> &str {
+        match self {
+            Format::SpaceTrack => name_line.strip_prefix("0 ").unwrap_or(name_line).trim(),
+            Format::ThreeLine | Format::CelesTrak => name_line.trim(),
+            Format::TwoLine => "",
+        }
+    }
+}
+
+/// Compute the checksum of a TLE line.
+///
+/// The checksum is the sum of all the digits in the first 68 columns,
+/// counting each minus sign as `1`, modulo 10.
+pub fn checksum(line: &[u8]) -> u8 {
+    let sum: u32 = line
+        .iter()
+        .take(68)
+        .map(|&c| match c {
+            b'0'..=b'9' => (c - b'0') as u32,
+            b'-' => 1,
+            _ => 0,
+        })
+        .sum();
+    (sum % 10) as u8
+}
+
+/// Mantissa and exponent of the decimal-point-assumed exponential notation, after rounding.
+fn exp_parts(x: f64) -> (i32, i32) {
+    // `{:.4e}` yields `d.dddde±x`, i.e. `0.ddddd × 10^(x+1)`
+    let s = format!("{:.4e}", x);
+    let (mantissa, exp) = s.split_once('e').unwrap();
+    let mantissa = mantissa.replace('.', "").parse().unwrap();
+    (mantissa, exp.parse::<i32>().unwrap() + 1)
+}
+
+/// Format a value in the decimal-point-assumed exponential notation
+/// used by the `ddn_o6` and `bstar` columns (e.g. ` 25302-4`).
+fn format_exp(x: f64) -> String {
+    let sign = if x < 0.0 { '-' } else { ' ' };
+    if x == 0.0 {
+        return format!("{}00000-0", sign);
+    }
+    let (mantissa, exp) = exp_parts(x.abs());
+    let exp_sign = if exp < 0 { '-' } else { '+' };
+    format!("{}{:05}{}{}", sign, mantissa, exp_sign, exp.abs())
+}
+
+/// Format the first derivative of the mean motion as ` .dddddddd`.
+fn format_dn_o2(x: f64) -> String {
+    let sign = if x.is_sign_negative() { '-' } else { ' ' };
+    let s = format!("{:.8}", x.abs());
+    format!("{}{}", sign, s.strip_prefix('0').unwrap_or(&s))
+}
+
+/// Value of a TLE which cannot be written in its columns.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FormatError {
+    #[error("catalog number {0:?} is not made of at most 5 ASCII characters")]
+    InvalidNorad(String),
+    #[error("name contains a line break")]
+    InvalidName,
+    #[error("international designator {0:?} is not made of at most 8 ASCII characters")]
+    InvalidIntDesig(String),
+    #[error("epoch year {0} out of range [1957, 2056]")]
+    EpochYearOutOfRange(i32),
+    #[error("first derivative of the mean motion {0} is not smaller than 1 in absolute value")]
+    DnO2TooLarge(f64),
+    #[error("{field} {value} cannot be written with a 5-digit mantissa and a 1-digit exponent")]
+    MantissaOutOfRange { field: &'static str, value: f64 },
+    #[error("element set number {0} has more than 4 digits")]
+    SetNumTooLarge(u32),
+    #[error("mean motion {0} does not fit in its column")]
+    MeanMotionTooLarge(f64),
+    #[error("revolution number {0} has more than 5 digits")]
+    RevNumTooLarge(u32),
+    #[error("{field} {value} does not fit in its column")]
+    NotEncodable { field: &'static str, value: f64 },
+}
+
+/// How to write the values which do not fit exactly in the TLE columns.
+///
+/// The default policy is [`EncodePolicy::STRICT`]. Values which do not fit in
+/// their columns even after rounding, such as a mean motion of 100 revolutions
+/// per day, are always an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EncodePolicy {
+    /// Write the revolution number modulo 100000 instead of failing
+    /// when it has more than 5 digits.
+    pub wrap_rev_num: bool,
+    /// Round the `ddn_o6` and `bstar` values to a 5-digit mantissa, the values
+    /// too small for the exponent becoming zero, instead of failing when they
+    /// are not exactly representable.
+    pub round_mantissas: bool,
+}
+
+impl EncodePolicy {
+    /// Fail on any value which cannot be written exactly.
+    pub const STRICT: EncodePolicy = EncodePolicy {
+        wrap_rev_num: false,
+        round_mantissas: false,
+    };
+    /// Wrap the revolution number and round the mantissas.
+    pub const LENIENT: EncodePolicy = EncodePolicy {
+        wrap_rev_num: true,
+        round_mantissas: true,
+    };
+}
+
+/// Values actually written in the columns which depend on the [`EncodePolicy`].
+struct Columns {
+    ddn_o6: f64,
+    bstar: f64,
+    rev_num: u32,
+}
+
+/// Check a value of a column in the decimal-point-assumed exponential notation.
+fn check_exp(field: &'static str, x: f64, round_mantissa: bool) -> Result<f64, FormatError> {
+    let error = FormatError::MantissaOutOfRange { field, value: x };
+    if x == 0.0 {
+        return Ok(x);
+    }
+    if !x.is_finite() {
+        return Err(error);
+    }
+    let (m, e) = exp_parts(x);
+    match e {
+        _ if e > 9 => Err(error),
+        _ if e < -9 && round_mantissa => Ok(0.0),
+        _ if e < -9 => Err(error),
+        _ if !round_mantissa && scale10(m as f64, e - 5) != x => Err(error),
+        // the value as written, which is `x` unless the mantissa was rounded
+        _ => Ok(scale10(m as f64, e - 5)),
+    }
+}
+
+impl TLE {
+    /// Check that the TLE can be written with the given policy.
+    pub fn check_encodable(&self, policy: EncodePolicy) -> Result<(), FormatError> {
+        self.columns(policy).map(|_| ())
+    }
+
+    fn columns(&self, policy: EncodePolicy) -> Result<Columns, FormatError> {
+        // whether the value, rounded to the given decimals, is in the range of scaled integers
+        // (`round` would turn a NaN into zero)
+        let fits = |x: f64, decimals: i32, range: core::ops::Range<f64>| {
+            x.is_finite() && range.contains(&round(scale10(x, decimals)))
+        };
+        let not_encodable = |field, value| FormatError::NotEncodable { field, value };
+
+        if !self.norad.is_ascii() || self.norad.len() > 5 {
+            return Err(FormatError::InvalidNorad(self.norad.clone()));
+        }
+        if self.name.contains(['\n', '\r']) {
+            return Err(FormatError::InvalidName);
+        }
+        if let IntDesigField::Raw(raw) = &self.int_desig {
+            if !raw.is_ascii() || raw.len() > 8 {
+                return Err(FormatError::InvalidIntDesig(raw.clone()));
+            }
+        }
+        // the year is written with two digits, which are read back in this range
+        if !(1957..=2056).contains(&self.epoch_year) {
+            return Err(FormatError::EpochYearOutOfRange(self.epoch_year));
+        }
+        if !fits(self.epoch_day, 8, 0.0..1e11) {
+            return Err(not_encodable("epoch_day", self.epoch_day));
+        }
+        if !fits(self.dn_o2.abs(), 8, 0.0..1e8) {
+            return Err(FormatError::DnO2TooLarge(self.dn_o2));
+        }
+        let ddn_o6 = check_exp("ddn_o6", self.ddn_o6, policy.round_mantissas)?;
+        let bstar = check_exp("bstar", self.bstar, policy.round_mantissas)?;
+        if self.set_num > 9999 {
+            return Err(FormatError::SetNumTooLarge(self.set_num));
+        }
+        for (field, x) in [("inc", self.inc), ("raan", self.raan)] {
+            if !fits(x, 4, -999_999.0..1e7) {
+                return Err(not_encodable(field, x));
+            }
+        }
+        if !fits(self.ecc, 7, 0.0..1e7) {
+            return Err(not_encodable("ecc", self.ecc));
+        }
+        for (field, x) in [("argp", self.argp), ("M", self.M)] {
+            if !fits(x, 4, -999_999.0..1e7) {
+                return Err(not_encodable(field, x));
+            }
+        }
+        if !fits(self.n, 8, -999_999_999.0..1e10) {
+            return Err(FormatError::MeanMotionTooLarge(self.n));
+        }
+        let rev_num = match self.rev_num {
+            r if r <= 99999 => r,
+            r if policy.wrap_rev_num => r % 100000,
+            r => return Err(FormatError::RevNumTooLarge(r)),
+        };
+        Ok(Columns {
+            ddn_o6,
+            bstar,
+            rev_num,
+        })
+    }
+
+    /// The TLE with the values actually written in the columns which depend on the policy:
+    /// the rounded `ddn_o6` and `bstar` and the wrapped revolution number.
+    pub(crate) fn encoded(mut self, policy: EncodePolicy) -> Result<TLE, FormatError> {
+        let columns = self.columns(policy)?;
+        self.ddn_o6 = columns.ddn_o6;
+        self.bstar = columns.bstar;
+        self.rev_num = columns.rev_num;
+        Ok(self)
+    }
+
+    /// Values of the columns written without checks.
+    fn unchecked_columns(&self) -> Columns {
+        Columns {
+            ddn_o6: self.ddn_o6,
+            bstar: self.bstar,
+            rev_num: self.rev_num,
+        }
+    }
+
+    /// Format the first line of the TLE, including the checksum.
+    ///
+    /// The values are not checked: the line is malformed if some of them do
+    /// not fit in their columns. See [`TLE::try_line1`].
+    pub fn line1(&self) -> String {
+        self.format_line1(&self.unchecked_columns())
+    }
+
+    /// Format the second line of the TLE, including the checksum.
+    ///
+    /// The values are not checked: the line is malformed if some of them do
+    /// not fit in their columns. See [`TLE::try_line2`].
+    pub fn line2(&self) -> String {
+        self.format_line2(&self.unchecked_columns())
+    }
+
+    /// Format the first line of the TLE, checking the values with the given policy.
+    pub fn try_line1(&self, policy: EncodePolicy) -> Result<String, FormatError> {
+        Ok(self.format_line1(&self.columns(policy)?))
+    }
+
+    /// Format the second line of the TLE, checking the values with the given policy.
+    pub fn try_line2(&self, policy: EncodePolicy) -> Result<String, FormatError> {
+        Ok(self.format_line2(&self.columns(policy)?))
+    }
+
+    fn format_line1(&self, columns: &Columns) -> String {
+        let mut line = format!(
+            "1 {:>5}{} {:<8} {:02}{:012.8} {} {} {} {} {:>4}",
+            self.norad,
+            self.classification,
+            self.int_desig,
+            self.epoch_year.rem_euclid(100),
+            self.epoch_day,
+            format_dn_o2(self.dn_o2),
+            format_exp(columns.ddn_o6),
+            format_exp(columns.bstar),
+            self.ephem_type,
+            self.set_num,
+        );
+        let c = checksum(line.as_bytes());
+        line.push((b'0' + c) as char);
+        line
+    }
+
+    fn format_line2(&self, columns: &Columns) -> String {
+        let mut line = format!(
+            "2 {:>5} {:>8.4} {:>8.4} {:07} {:>8.4} {:>8.4} {:>11.8}{:>5}",
+            self.norad,
+            self.inc,
+            self.raan,
+            round(self.ecc * 10_000_000f64) as u32,
+            self.argp,
+            self.M,
+            self.n,
+            columns.rev_num,
+        );
+        let c = checksum(line.as_bytes());
+        line.push((b'0' + c) as char);
+        line
+    }
+}
+
+impl TLE {
+    /// Format the TLE as a record of the given format, with lines separated by `\n`.
+    ///
+    /// The values are not checked: see [`TLE::try_to_string_in`].
+    pub fn to_string_in(&self, format: Format) -> String {
+        self.record(format, self.line1(), self.line2())
+    }
+
+    /// Format the TLE as a record of the given format, checking the values with the given policy.
+    pub fn try_to_string_in(
+        &self,
+        format: Format,
+        policy: EncodePolicy,
+    ) -> Result<String, FormatError> {
+        let columns = self.columns(policy)?;
+        Ok(self.record(
+            format,
+            self.format_line1(&columns),
+            self.format_line2(&columns),
+        ))
+    }
+
+    fn record(&self, format: Format, line1: String, line2: String) -> String {
+        match format {
+            Format::ThreeLine => format!("{}\n{}\n{}", self.name, line1, line2),
+            Format::SpaceTrack => format!("0 {}\n{}\n{}", self.name, line1, line2),
+            Format::CelesTrak => {
+                let name: String = self.name.chars().take(CELESTRAK_NAME_LEN).collect();
+                format!("{:<24}\n{}\n{}", name, line1, line2)
+            }
+            Format::TwoLine => format!("{}\n{}", line1, line2),
+        }
+    }
+}
+
+/// Error of [`try_write_all`].
+#[cfg(feature = "std")]
+#[derive(Error, Debug)]
+pub enum WriteError {
+    #[error("I/O error")]
+    IoError(#[from] io::Error),
+    #[error("TLE of object {norad} cannot be written")]
+    FormatError {
+        norad: String,
+        #[source]
+        source: FormatError,
+    },
+}
+
+/// Write the TLEs as records of the given format, each line terminated by `\n`.
+///
+/// **The values are not checked**: a TLE with a value which does not fit in
+/// its column is written as malformed lines, which other readers reject or
+/// misread. Use [`try_write_all`] to detect such values.
+#[cfg(feature = "std")]
+pub fn write_all<'a, W, I>(mut writer: W, tles: I, format: Format) -> io::Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = &'a TLE>,
+{
+    for tle in tles {
+        writeln!(writer, "{}", tle.to_string_in(format))?;
+    }
+    Ok(())
+}
+
+/// Write the TLEs as records of the given format, checking the values with the given policy.
+///
+/// Writing stops at the first TLE which cannot be written, the previous ones
+/// having been written.
+#[cfg(feature = "std")]
+pub fn try_write_all<'a, W, I>(
+    mut writer: W,
+    tles: I,
+    format: Format,
+    policy: EncodePolicy,
+) -> Result<(), WriteError>
+where
+    W: io::Write,
+    I: IntoIterator<Item = &'a TLE>,
+{
+    for tle in tles {
+        let record =
+            tle.try_to_string_in(format, policy)
+                .map_err(|source| WriteError::FormatError {
+                    norad: tle.norad.clone(),
+                    source,
+                })?;
+        writeln!(writer, "{}", record)?;
+    }
+    Ok(())
+}
+
+/// Formats the TLE as three lines (name, line 1 and line 2) separated by `\n`.
+///
+/// **The values are not checked**, as with [`TLE::line1`] and [`TLE::line2`]:
+/// use [`TLE::try_to_string_in`] to detect the values which do not fit in their columns.
+impl fmt::Display for TLE {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}\n{}", self.name, self.line1(), self.line2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_checksum() {
+        for line in ISS.lines().skip(1) {
+            let line = line.as_bytes();
+            assert_eq!(checksum(line), line[68] - b'0');
+        }
+    }
+
+    #[test]
+    fn test_format_exp() {
+        assert_eq!(format_exp(0.0), " 00000-0");
+        assert_eq!(format_exp(2.5302e-5), " 25302-4");
+        assert_eq!(format_exp(-1.1606e-5), "-11606-4");
+        assert_eq!(format_exp(0.12345), " 12345+0");
+    }
+
+    #[test]
+    fn test_encodability() {
+        let tle = crate::parse(ISS).unwrap();
+        assert_eq!(tle.check_encodable(EncodePolicy::STRICT), Ok(()));
+        assert_eq!(
+            tle.try_to_string_in(Format::ThreeLine, EncodePolicy::STRICT),
+            Ok(ISS.to_string())
+        );
+
+        let check = |tle: &TLE| tle.check_encodable(EncodePolicy::STRICT);
+        let n = TLE {
+            n: 99.999999999,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(
+            check(&n),
+            Err(FormatError::MeanMotionTooLarge(99.999999999))
+        );
+        let dn_o2 = TLE {
+            dn_o2: -1.0,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(check(&dn_o2), Err(FormatError::DnO2TooLarge(-1.0)));
+        let set_num = TLE {
+            set_num: 10000,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(check(&set_num), Err(FormatError::SetNumTooLarge(10000)));
+        let int_desig = TLE {
+            int_desig: IntDesigField::Raw("98067AAAA".to_string()),
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(
+            check(&int_desig),
+            Err(FormatError::InvalidIntDesig("98067AAAA".to_string()))
+        );
+        let inc = TLE {
+            inc: -100.0,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert!(matches!(
+            check(&inc),
+            Err(FormatError::NotEncodable { field: "inc", .. })
+        ));
+
+        let tle = TLE {
+            bstar: 1.234567e-5,
+            ddn_o6: -1e-12,
+            rev_num: 123456,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert!(matches!(
+            check(&tle),
+            Err(FormatError::MantissaOutOfRange {
+                field: "ddn_o6",
+                ..
+            })
+        ));
+        let line1 = tle.try_line1(EncodePolicy::LENIENT).unwrap();
+        assert_eq!(&line1[44..61], " 00000-0  12346-4");
+        assert_eq!(
+            tle.try_line2(EncodePolicy::LENIENT).unwrap()[63..68],
+            *"23456"
+        );
+        let rev_num = TLE {
+            rev_num: 123456,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(check(&rev_num), Err(FormatError::RevNumTooLarge(123456)));
+        let wrap = EncodePolicy {
+            wrap_rev_num: true,
+            ..EncodePolicy::STRICT
+        };
+        assert!(rev_num.try_line2(wrap).is_ok());
+        let bstar = TLE {
+            bstar: 1e10,
+            ..crate::parse(ISS).unwrap()
+        };
+        assert!(bstar.check_encodable(EncodePolicy::LENIENT).is_err());
+
+        let norad = TLE {
+            norad: "ÀÉÎÕÜ".into(),
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(
+            check(&norad),
+            Err(FormatError::InvalidNorad("ÀÉÎÕÜ".into()))
+        );
+        let name = TLE {
+            name: "ISS\n(ZARYA)".into(),
+            ..crate::parse(ISS).unwrap()
+        };
+        assert_eq!(check(&name), Err(FormatError::InvalidName));
+        for year in [1956, 2057] {
+            let tle = TLE {
+                epoch_year: year,
+                ..crate::parse(ISS).unwrap()
+            };
+            assert_eq!(check(&tle), Err(FormatError::EpochYearOutOfRange(year)));
+        }
+
+        type Setter = fn(&mut TLE, f64);
+        let columns: [(&str, Setter); 8] = [
+            ("epoch_day", |tle, x| tle.epoch_day = x),
+            ("dn_o2", |tle, x| tle.dn_o2 = x),
+            ("inc", |tle, x| tle.inc = x),
+            ("raan", |tle, x| tle.raan = x),
+            ("ecc", |tle, x| tle.ecc = x),
+            ("argp", |tle, x| tle.argp = x),
+            ("M", |tle, x| tle.M = x),
+            ("n", |tle, x| tle.n = x),
+        ];
+        for (field, set) in columns {
+            for x in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+                let mut tle = crate::parse(ISS).unwrap();
+                set(&mut tle, x);
+                assert!(check(&tle).is_err(), "{} {}", field, x);
+                assert!(tle
+                    .try_to_string_in(Format::TwoLine, EncodePolicy::LENIENT)
+                    .is_err());
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_try_write_all() {
+        let iss = crate::parse(ISS).unwrap();
+        let broken = TLE {
+            n: 100.0,
+            ..crate::parse(ISS).unwrap()
+        };
+        let mut out = Vec::new();
+        try_write_all(&mut out, [&iss], Format::ThreeLine, EncodePolicy::STRICT).unwrap();
+        assert_eq!(out, format!("{}\n", ISS).into_bytes());
+
+        let mut out = Vec::new();
+        let result = try_write_all(
+            &mut out,
+            [&iss, &broken],
+            Format::TwoLine,
+            EncodePolicy::STRICT,
+        );
+        assert!(matches!(
+            result,
+            Err(WriteError::FormatError { norad, source: FormatError::MeanMotionTooLarge(_) })
+                if norad == "25544"
+        ));
+        assert_eq!(out.len(), 2 * 70);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tle = crate::parse(ISS).unwrap();
+        assert_eq!(tle.to_string(), ISS);
+    }
+
+    #[test]
+    fn test_formats() {
+        let tle = crate::parse(ISS).unwrap();
+        for format in [
+            Format::ThreeLine,
+            Format::SpaceTrack,
+            Format::CelesTrak,
+            Format::TwoLine,
+        ] {
+            let s = tle.to_string_in(format);
+            let name_line = if format.has_name() {
+                s.lines().next()
+            } else {
+                None
+            };
+            assert_eq!(Format::detect(name_line), format);
+            assert_eq!(
+                crate::parse(&s).unwrap().name,
+                format.name(name_line.unwrap_or(""))
+            );
+        }
+        assert!(tle
+            .to_string_in(Format::CelesTrak)
+            .starts_with("ISS (ZARYA)             \n"));
+        assert!(tle
+            .to_string_in(Format::SpaceTrack)
+            .starts_with("0 ISS (ZARYA)\n"));
+    }
+}
@@ -1,22 +1,44 @@
+use alloc::string::String;
+use core::str::FromStr;
+
 use thiserror::Error;
 
-use crate::TLE;
+use crate::digits;
+use crate::epoch::full_year;
+use crate::format::is_line1;
+use crate::math::scale10;
+use crate::name::{decode_name, normalize_name};
+use crate::{Classification, EphemerisType, Format, IntDesigField, TLE};
 
-pub type BoxError = std::boxed::Box<dyn std::error::Error + std::marker::Send + std::marker::Sync>;
+pub type BoxError =
+    alloc::boxed::Box<dyn core::error::Error + core::marker::Send + core::marker::Sync>;
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 #[error("invalid TLE string")]
 pub enum Error {
     #[error("invalid encoding")]
-    EncodingError(#[from] std::str::Utf8Error),
+    EncodingError(#[from] core::str::Utf8Error),
     #[error("invalid format, reason: {0}")]
     InvalidFormat(&'static str),
+    #[error("non-ASCII character in line {0}")]
+    NonAsciiLine(u8),
     #[error("cannot parse {0}")]
     ParseError(&'static str),
     #[error("cannot parse int")]
-    ParseIntError(#[from] std::num::ParseIntError),
+    ParseIntError(#[from] core::num::ParseIntError),
     #[error("cannot parse float")]
-    ParseFloatError(#[from] std::num::ParseFloatError),
+    ParseFloatError(#[from] core::num::ParseFloatError),
+    #[error("invalid classification {0:?}")]
+    InvalidClassification(char),
+    #[error("invalid ephemeris type {0:?}")]
+    InvalidEphemerisType(char),
+    #[error("invalid international designator, reason: {0}")]
+    InvalidIntDesig(&'static str),
+    #[error("invalid elements, reason: {0}")]
+    InvalidElements(&'static str),
+    #[cfg(feature = "sgp4")]
+    #[error("propagation error: {0}")]
+    PropagationError(#[from] crate::PropagationError),
 }
 
 macro_rules! get_next_or_incomplete_error {
@@ -47,6 +69,40 @@ macro_rules! ensure_is_space {
     };
 }
 
+fn ensure_ascii(line: &[u8], number: u8) -> Result<(), Error> {
+    if line.is_ascii() {
+        Ok(())
+    } else {
+        Err(Error::NonAsciiLine(number))
+    }
+}
+
+/// Parse a column with one of the parsers of [`digits`].
+///
+/// The columns they reject are parsed again with `str::parse`, so that the
+/// accepted values and the errors ([`Error::ParseIntError`],
+/// [`Error::ParseFloatError`]) are those of the standard parsers.
+fn field<T: FromStr>(parse: fn(&[u8]) -> Option<T>, s: &[u8]) -> Result<T, Error>
+where
+    Error: From<T::Err>,
+{
+    match parse(s) {
+        Some(x) => Ok(x),
+        None => Ok(core::str::from_utf8(s)?.parse()?),
+    }
+}
+
+/// Parse a column which may be padded with spaces, like [`field`].
+fn padded_field<T: FromStr>(parse: fn(&[u8]) -> Option<T>, s: &[u8]) -> Result<T, Error>
+where
+    Error: From<T::Err>,
+{
+    match parse(s) {
+        Some(x) => Ok(x),
+        None => Ok(core::str::from_utf8(s)?.trim().parse()?),
+    }
+}
+
 fn ensure_line_length_and_termination(line: &[u8]) -> Result<&[u8], Error> {
     if !(line.len() == 69 || (line.len() == 70 && line[69] == b'\n')) {
         Err(Error::InvalidFormat("incorrect line length"))
@@ -55,39 +111,62 @@ fn ensure_line_length_and_termination(line: &[u8]) -> Result<&[u8], Error> {
     }
 }
 
-// fn parse_special_float(s: &[u8]) -> Result<f64, std::num::ParseIntError> {
-//     assert!(s.len() == 8);
-//     let m = std::str::from_utf8(&s[0..=5])
-//         .unwrap()
-//         .trim()
-//         .parse::<i32>();
-//     let e = std::str::from_utf8(&s[6..=7]).unwrap().parse::<i32>();
-//     match (m, e) {
-//         (Ok(m), Ok(e)) => {
-//             let value = m as f64 * 10f64.powi(e - 5);
-//             Ok(value)
-//         }
-//         (Err(e), _) | (_, Err(e)) => {
-//             eprintln!("{:?}", std::str::from_utf8(s).unwrap());
-//             Err(e)
-//         }
-//     }
-// }
-
-/// Parse a TLE from a string representing three lines.
+/// Parse a TLE from a string representing a single record.
+///
+/// The record can be in any of the layouts described by [`Format`]:
+/// two lines without name, or three lines with a name possibly prefixed by `0 `.
 pub fn parse(tle_str: &str) -> Result<TLE, Error> {
-    let mut lines_iter = tle_str.lines();
-    get_next_or_incomplete_error!(name, lines_iter);
+    parse_bytes(tle_str.as_bytes())
+}
+
+/// Parse a TLE from bytes representing a single record.
+///
+/// The input does not need to be valid UTF-8: see [`from_line_bytes`]
+/// for the treatment of non-ASCII bytes. Lines are terminated by `\n` or `\r\n`.
+pub fn parse_bytes(tle: &[u8]) -> Result<TLE, Error> {
+    let mut lines_iter = tle
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+    get_next_or_incomplete_error!(first, lines_iter);
+    if is_line1(first) {
+        get_next_or_incomplete_error!(line2, lines_iter);
+        return from_line_bytes(b"", first, line2);
+    }
+    let name = decode_name(first);
     get_next_or_incomplete_error!(line1, lines_iter);
     get_next_or_incomplete_error!(line2, lines_iter);
-    from_lines(name, line1, line2)
+    from_line_bytes(
+        Format::detect(Some(&name)).name(&name).as_bytes(),
+        line1,
+        line2,
+    )
 }
 
 /// Parse a TLE from the three individual lines.
+///
+/// The name is trimmed and the Space-Track `0 ` prefix is removed;
+/// see [`TLE::name_metadata`] for the information it contains.
+///
+/// The element lines must be ASCII: a line containing any other character
+/// fails with [`Error::NonAsciiLine`] (rather than [`Error::EncodingError`]).
+/// A column which cannot be parsed fails with [`Error::ParseIntError`] or
+/// [`Error::ParseFloatError`].
 pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
-    let name = name.trim().to_string();
-    let line1 = line1.as_bytes();
-    let line2 = line2.as_bytes();
+    from_line_bytes(name.as_bytes(), line1.as_bytes(), line2.as_bytes())
+}
+
+/// Parse a TLE from the three individual lines given as bytes.
+///
+/// This is the byte-oriented counterpart of [`from_lines`], for input whose
+/// encoding is not known. The columns are parsed directly from the bytes,
+/// without converting the lines to strings. The name is decoded as UTF-8 if it is valid,
+/// otherwise as Latin-1, so that it never causes an error. The element lines
+/// must be ASCII: a line containing any other byte fails with
+/// [`Error::NonAsciiLine`] before its columns are examined.
+pub fn from_line_bytes(name: &[u8], line1: &[u8], line2: &[u8]) -> Result<TLE, Error> {
+    let name = normalize_name(&decode_name(name));
+    ensure_ascii(line1, 1)?;
+    ensure_ascii(line2, 2)?;
 
     // LINE 1
 
@@ -96,55 +175,45 @@ pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
     ensure_is!(line1[0], b'1');
     ensure_is_space!(line1[1]);
 
-    let norad = std::str::from_utf8(&line1[2..=6])?.trim().to_string();
-    let classification = line1[7] as char;
+    let norad: String = line1[2..=6]
+        .trim_ascii()
+        .iter()
+        .map(|&c| char::from(c))
+        .collect();
+    let classification = Classification::try_from(line1[7] as char)?;
 
     ensure_is_space!(line1[8]);
 
-    let int_desig = std::str::from_utf8(&line1[9..=16])?.trim().to_string();
+    let int_desig = IntDesigField::from_columns(&line1[9..=16]);
 
     ensure_is_space!(line1[17]);
 
-    let y = std::str::from_utf8(&line1[18..=19])?.parse::<u8>()?;
-    let epoch_year = if y <= 56 {
-        2000 + y as i32
-    } else {
-        1900 + y as i32
-    };
+    let y = field(digits::u8_2_digits, &line1[18..=19])?;
+    let epoch_year = full_year(y);
 
-    let epoch_day = std::str::from_utf8(&line1[20..=31])?.parse::<f64>()?;
+    let epoch_day = field(digits::epoch_day, &line1[20..=31])?;
 
     ensure_is_space!(line1[32]);
 
-    let dn_o2 = std::str::from_utf8(&line1[33..=42])?
-        .trim()
-        .parse::<f64>()?;
+    let dn_o2 = padded_field(digits::padded_f64, &line1[33..=42])?;
 
     ensure_is_space!(line1[43]);
 
-    let m = std::str::from_utf8(&line1[44..=49])?
-        .trim()
-        .parse::<i32>()?;
-    let e = std::str::from_utf8(&line1[50..=51])?.parse::<i32>()?;
-    let ddn_o6 = m as f64 * 10f64.powi(e - 5);
-    // let ddn_o6 = parse_special_float(&line1[44..=51])?;
+    let m = padded_field(digits::padded_i32, &line1[44..=49])?;
+    let e = field(digits::signed_i32, &line1[50..=51])?;
+    let ddn_o6 = scale10(m as f64, e - 5);
 
     ensure_is_space!(line1[52]);
 
-    let m = std::str::from_utf8(&line1[53..=58])?
-        .trim()
-        .parse::<i32>()?;
-    let e = std::str::from_utf8(&line1[59..=60])?.parse::<i32>()?;
-    let bstar = m as f64 * 10f64.powi(e - 5);
-    // let bstar = parse_special_float(&line1[53..=60])?;
+    let m = padded_field(digits::padded_i32, &line1[53..=58])?;
+    let e = field(digits::signed_i32, &line1[59..=60])?;
+    let bstar = scale10(m as f64, e - 5);
 
     ensure_is_space!(line1[61]);
-    ensure_is!(line1[62], b'0');
+    let ephem_type = EphemerisType::try_from(line1[62] as char)?;
     ensure_is_space!(line1[63]);
 
-    let set_num = std::str::from_utf8(&line1[64..=67])?
-        .trim()
-        .parse::<u32>()?;
+    let set_num = padded_field(digits::padded_u32, &line1[64..=67])?;
 
     // LINE 2
 
@@ -153,48 +222,37 @@ pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
     ensure_is!(line2[0], b'2');
     ensure_is_space!(line2[1]);
 
-    let norad2 = std::str::from_utf8(&line2[2..=6])?.to_string();
-    if norad2 != norad {
+    if line2[2..=6] != *norad.as_bytes() {
         invalid_error!("norad on line 1 and 2 are different");
     }
 
     ensure_is_space!(line2[7]);
 
-    let inc = std::str::from_utf8(&line2[8..=15])?.trim().parse::<f64>()?;
+    let inc = padded_field(digits::f64_3_4_padded, &line2[8..=15])?;
 
     ensure_is_space!(line2[16]);
 
-    let raan = std::str::from_utf8(&line2[17..=24])?
-        .trim()
-        .parse::<f64>()?;
+    let raan = padded_field(digits::f64_3_4_padded, &line2[17..=24])?;
 
     ensure_is_space!(line2[25]);
 
-    let e = std::str::from_utf8(&line2[26..=32])?.parse::<u32>()?;
+    let e = field(digits::u32_7_digits, &line2[26..=32])?;
     let ecc = e as f64 / 10_000_000f64;
 
     ensure_is_space!(line2[33]);
 
-    let argp = std::str::from_utf8(&line2[34..=41])?
-        .trim()
-        .parse::<f64>()?;
+    let argp = padded_field(digits::f64_3_4_padded, &line2[34..=41])?;
 
     ensure_is_space!(line2[42]);
 
     #[allow(non_snake_case)]
-    let M = std::str::from_utf8(&line2[43..=50])?
-        .trim()
-        .parse::<f64>()?;
+    let M = padded_field(digits::f64_3_4_padded, &line2[43..=50])?;
 
     ensure_is_space!(line2[51]);
 
-    let n = std::str::from_utf8(&line2[52..=62])?
-        .trim()
-        .parse::<f64>()?;
+    let n = padded_field(digits::padded_f64, &line2[52..=62])?;
 
-    let rev_num = std::str::from_utf8(&line2[63..=67])?
-        .trim()
-        .parse::<u32>()?;
+    let rev_num = padded_field(digits::padded_u32, &line2[63..=67])?;
 
     // TODO: checksum
 
@@ -208,6 +266,7 @@ pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
         dn_o2,
         ddn_o6,
         bstar,
+        ephem_type,
         set_num,
         inc,
         raan,
@@ -218,3 +277,71 @@ pub fn from_lines(name: &str, line1: &str, line2: &str) -> Result<TLE, Error> {
         rev_num,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990";
+    const LINE2: &str = "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_non_ascii() {
+        let mut record = b"0 S\xc3O PAULO\r\n".to_vec();
+        record.extend_from_slice(format!("{LINE1}\r\n{LINE2}\r\n").as_bytes());
+        let tle = parse_bytes(&record).unwrap();
+        assert_eq!(tle.name, "SÃO PAULO");
+        assert_eq!(tle, from_lines("SÃO PAULO", LINE1, LINE2).unwrap());
+
+        // a multi-byte character replacing a single column
+        let line1 = LINE1.replacen("U", "Ü", 1);
+        assert_eq!(
+            from_lines("", &line1[..69], LINE2),
+            Err(Error::NonAsciiLine(1))
+        );
+        let line2 = LINE2.replacen('.', "\u{b7}", 1);
+        assert_eq!(from_lines("", LINE1, &line2), Err(Error::NonAsciiLine(2)));
+        let mut line2 = LINE2.as_bytes().to_vec();
+        line2[10] = 0xb7;
+        assert_eq!(
+            from_line_bytes(b"", LINE1.as_bytes(), &line2),
+            Err(Error::NonAsciiLine(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line1 = LINE1.replacen("20045", "2x045", 1);
+        assert!(matches!(
+            from_lines("", &line1, LINE2),
+            Err(Error::ParseIntError(_))
+        ));
+        let line2 = LINE2.replacen("15.49165514", "15.4916551x", 1);
+        assert!(matches!(
+            from_lines("", LINE1, &line2),
+            Err(Error::ParseFloatError(_))
+        ));
+        // the exponents are not padded
+        let line1 = LINE1.replacen("25302-4", "25302 4", 1);
+        assert!(matches!(
+            from_lines("", &line1, LINE2),
+            Err(Error::ParseIntError(_))
+        ));
+        // forms outside the canonical layout are accepted as before
+        let line2 = LINE2.replacen(" 51.6443", "51.644  ", 1);
+        assert_eq!(from_lines("", LINE1, &line2).unwrap().inc, 51.644);
+    }
+
+    #[test]
+    fn test_nonstandard_int_desig() {
+        // designators outside the standard form are kept as written
+        for columns in ["98000A  ", "98067a  ", " 98067A ", "ANALYST "] {
+            let line1 = LINE1.replacen("98067A  ", columns, 1);
+            let tle = from_lines("", &line1, LINE2).unwrap();
+            assert_eq!(tle.int_desig, IntDesigField::Raw(columns.trim_end().into()));
+            assert_eq!(tle.line1()[..64], line1[..64]);
+        }
+        let tle = from_lines("", LINE1, LINE2).unwrap();
+        assert!(tle.int_desig.parsed().is_some());
+    }
+}
@@ -0,0 +1,261 @@
+use alloc::format;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::epoch::full_year;
+use crate::Error;
+
+/// International designator (COSPAR ID) of an object.
+///
+/// It is made of the launch year, the launch number of the year and
+/// the piece of the launch (one to three letters). Designators are ordered
+/// chronologically, then by piece (`A` < `Z` < `AA` < `ZZZ`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntDesig {
+    year: i32,
+    launch: u16,
+    piece: [u8; 3],
+}
+
+impl IntDesig {
+    /// Create a designator, validating its components.
+    ///
+    /// The year must be representable with two digits in a TLE (1957–2056),
+    /// the launch number must be in `1..=999` and the piece must be made of
+    /// one to three uppercase ASCII letters.
+    pub fn new(year: i32, launch: u16, piece: &str) -> Result<IntDesig, Error> {
+        if !(1957..=2056).contains(&year) {
+            return Err(Error::InvalidIntDesig("year out of range"));
+        }
+        if !(1..=999).contains(&launch) {
+            return Err(Error::InvalidIntDesig("launch number out of range"));
+        }
+        let bytes = piece.as_bytes();
+        if bytes.is_empty() || bytes.len() > 3 || !bytes.iter().all(u8::is_ascii_uppercase) {
+            return Err(Error::InvalidIntDesig("invalid piece"));
+        }
+        let mut p = [b' '; 3];
+        p[..bytes.len()].copy_from_slice(bytes);
+        Ok(IntDesig {
+            year,
+            launch,
+            piece: p,
+        })
+    }
+
+    /// Parse the designator from the columns 10–17 of the first line of a TLE (e.g. `98067A  `).
+    ///
+    /// Returns `None` if the columns are blank.
+    pub fn from_tle_columns(s: &[u8]) -> Result<Option<IntDesig>, Error> {
+        let s = core::str::from_utf8(s)?.trim_end();
+        if s.trim_start().is_empty() {
+            return Ok(None);
+        }
+        let digits = |r: core::ops::Range<usize>| {
+            s.get(r)
+                .filter(|d| d.bytes().all(|c| c.is_ascii_digit()))
+                .ok_or(Error::InvalidIntDesig("expected digits"))
+        };
+        let year = full_year(digits(0..2)?.parse()?);
+        let launch = digits(2..5)?.parse()?;
+        IntDesig::new(year, launch, &s[5..]).map(Some)
+    }
+
+    /// Launch year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Launch number of the year.
+    pub fn launch(&self) -> u16 {
+        self.launch
+    }
+
+    /// Piece of the launch.
+    pub fn piece(&self) -> &str {
+        let len = self.piece.iter().position(|&c| c == b' ').unwrap_or(3);
+        core::str::from_utf8(&self.piece[..len]).unwrap()
+    }
+
+    /// COSPAR form of the designator (e.g. `1998-067A`).
+    pub fn cospar(&self) -> String {
+        format!("{}-{:03}{}", self.year, self.launch, self.piece())
+    }
+}
+
+impl Ord for IntDesig {
+    fn cmp(&self, other: &IntDesig) -> Ordering {
+        (self.year, self.launch, self.piece().len(), self.piece).cmp(&(
+            other.year,
+            other.launch,
+            other.piece().len(),
+            other.piece,
+        ))
+    }
+}
+
+impl PartialOrd for IntDesig {
+    fn partial_cmp(&self, other: &IntDesig) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats the designator as in a TLE (e.g. `98067A`).
+impl fmt::Display for IntDesig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = format!(
+            "{:02}{:03}{}",
+            self.year.rem_euclid(100),
+            self.launch,
+            self.piece()
+        );
+        f.pad(&s)
+    }
+}
+
+/// Content of the international designator columns (10–17 of the first line) of a TLE.
+///
+/// Catalogs contain designators which do not follow the standard form (launch
+/// number `000`, lowercase pieces, analyst objects): their text is kept so
+/// that the TLE can be written back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum IntDesigField {
+    /// A designator in the standard form.
+    Parsed(IntDesig),
+    /// Text of the columns, without the trailing spaces, when they are
+    /// neither blank nor a valid designator, such as `98000A`.
+    Raw(String),
+    /// Blank columns.
+    #[default]
+    Blank,
+}
+
+impl IntDesigField {
+    /// Read the columns of the designator.
+    pub(crate) fn from_columns(s: &[u8]) -> IntDesigField {
+        match IntDesig::from_tle_columns(s) {
+            Ok(int_desig) => int_desig.into(),
+            Err(_) => IntDesigField::Raw(String::from_utf8_lossy(s.trim_ascii_end()).into_owned()),
+        }
+    }
+
+    /// The designator, if the columns hold one in the standard form.
+    pub fn parsed(&self) -> Option<IntDesig> {
+        match self {
+            IntDesigField::Parsed(int_desig) => Some(*int_desig),
+            _ => None,
+        }
+    }
+}
+
+impl From<Option<IntDesig>> for IntDesigField {
+    fn from(int_desig: Option<IntDesig>) -> IntDesigField {
+        int_desig.map_or(IntDesigField::Blank, IntDesigField::Parsed)
+    }
+}
+
+/// Formats the text of the columns, without padding.
+impl fmt::Display for IntDesigField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntDesigField::Parsed(int_desig) => int_desig.fmt(f),
+            IntDesigField::Raw(raw) => f.pad(raw),
+            IntDesigField::Blank => f.pad(""),
+        }
+    }
+}
+
+/// Parses either the TLE form (`98067A`) or the COSPAR form (`1998-067A`).
+impl FromStr for IntDesig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<IntDesig, Error> {
+        let s = s.trim();
+        match s.split_once('-') {
+            Some((year, rest)) if year.len() == 4 && rest.len() > 3 => {
+                let launch = rest
+                    .get(..3)
+                    .ok_or(Error::InvalidIntDesig("expected digits"))?;
+                if !year
+                    .bytes()
+                    .chain(launch.bytes())
+                    .all(|c| c.is_ascii_digit())
+                {
+                    return Err(Error::InvalidIntDesig("expected digits"));
+                }
+                IntDesig::new(year.parse()?, launch.parse()?, &rest[3..])
+            }
+            Some(_) => Err(Error::InvalidIntDesig("invalid COSPAR form")),
+            None => IntDesig::from_tle_columns(s.as_bytes())?
+                .ok_or(Error::InvalidIntDesig("empty designator")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns() {
+        let d = IntDesig::from_tle_columns(b"98067A  ").unwrap().unwrap();
+        let field = IntDesigField::from_columns(b"98067A  ");
+        assert_eq!(field, IntDesigField::Parsed(d));
+        assert_eq!(field.parsed(), Some(d));
+        assert_eq!(format!("{:<8}", field), "98067A  ");
+        assert_eq!(
+            IntDesigField::from_columns(b"        "),
+            IntDesigField::Blank
+        );
+        assert_eq!(IntDesigField::Blank.to_string(), "");
+        for columns in ["98000A", "98067a", " 98067A", "ANALYST"] {
+            let padded = format!("{:<8}", columns);
+            let field = IntDesigField::from_columns(padded.as_bytes());
+            assert_eq!(field, IntDesigField::Raw(columns.into()));
+            assert_eq!(field.parsed(), None);
+            assert_eq!(format!("{:<8}", field), padded);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let d = IntDesig::from_tle_columns(b"98067A  ").unwrap().unwrap();
+        assert_eq!(d.year(), 1998);
+        assert_eq!(d.launch(), 67);
+        assert_eq!(d.piece(), "A");
+        assert_eq!(d.cospar(), "1998-067A");
+        assert_eq!(d.to_string(), "98067A");
+        assert_eq!(format!("{:<8}|", d), "98067A  |");
+        assert_eq!("1998-067A".parse::<IntDesig>().unwrap(), d);
+        assert_eq!("98067A".parse::<IntDesig>().unwrap(), d);
+
+        let d = IntDesig::from_tle_columns(b"20001ABC").unwrap().unwrap();
+        assert_eq!(d.cospar(), "2020-001ABC");
+
+        assert_eq!(IntDesig::from_tle_columns(b"        ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(IntDesig::from_tle_columns(b"98067a  ").is_err());
+        assert!(IntDesig::from_tle_columns(b"98A67A  ").is_err());
+        assert!(IntDesig::from_tle_columns(b"98000A  ").is_err());
+        assert!(IntDesig::from_tle_columns(b"98067   ").is_err());
+        assert!(IntDesig::from_tle_columns(b"98067ABCD").is_err());
+        assert!("1998-67A".parse::<IntDesig>().is_err());
+        assert!(IntDesig::new(1956, 1, "A").is_err());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut designators: Vec<IntDesig> = ["98067AA", "98067B", "57001A", "98067A", "20001A"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        designators.sort();
+        let sorted: Vec<String> = designators.iter().map(|d| d.to_string()).collect();
+        assert_eq!(sorted, ["57001A", "98067A", "98067B", "98067AA", "20001A"]);
+    }
+}
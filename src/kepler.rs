@@ -0,0 +1,452 @@
+use std::f64::consts::{PI, TAU};
+
+#[cfg(feature = "sgp4")]
+use crate::epoch::year_and_day;
+#[cfg(feature = "sgp4")]
+use crate::IntDesig;
+use crate::{Error, TLE};
+
+/// Gravitational parameter of the Earth in km³/s² (WGS-84, as used by the propagator).
+pub const EARTH_MU: f64 = 398_600.5;
+
+/// Equatorial radius of the Earth in km (WGS-84).
+pub const EARTH_RADIUS: f64 = 6378.137;
+
+const DEG: f64 = PI / 180.0;
+
+/// Osculating Keplerian elements of an elliptic Earth orbit.
+///
+/// Angles are expressed in degrees, as in [`TLE`](crate::TLE).
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct KeplerianElements {
+    /// Semi-major axis (km).
+    pub a: f64,
+    /// Eccentricity (`0 ≤ ecc < 1`).
+    pub ecc: f64,
+    /// Inclination.
+    pub inc: f64,
+    /// Right ascension of the ascending node.
+    pub raan: f64,
+    /// Argument of perigee.
+    pub argp: f64,
+    /// Mean anomaly.
+    pub M: f64,
+}
+
+/// Cartesian position and velocity in an Earth-centered inertial frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StateVector {
+    /// Position (km).
+    pub position: [f64; 3],
+    /// Velocity (km/s).
+    pub velocity: [f64; 3],
+}
+
+pub(crate) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Angle in degrees normalized to `[0, 360)`.
+fn normalize_deg(x: f64) -> f64 {
+    let x = x.rem_euclid(360.0);
+    if x >= 360.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+/// Solve Kepler's equation `M = E - e sin E` for the eccentric anomaly (radians).
+fn eccentric_anomaly(mean_anomaly: f64, ecc: f64) -> f64 {
+    let mut e = if ecc < 0.8 { mean_anomaly } else { PI };
+    for _ in 0..50 {
+        let delta = (e - ecc * e.sin() - mean_anomaly) / (1.0 - ecc * e.cos());
+        e -= delta;
+        if delta.abs() < 1e-15 {
+            break;
+        }
+    }
+    e
+}
+
+impl KeplerianElements {
+    /// Mean motion in revolutions per day.
+    pub fn mean_motion(&self) -> f64 {
+        (EARTH_MU / self.a.powi(3)).sqrt() * 86400.0 / TAU
+    }
+
+    /// Orbital period in minutes.
+    pub fn period(&self) -> f64 {
+        TAU * (self.a.powi(3) / EARTH_MU).sqrt() / 60.0
+    }
+
+    /// Convert the elements to a Cartesian state vector.
+    pub fn to_state_vector(&self) -> StateVector {
+        let e = self.ecc;
+        let ea = eccentric_anomaly(self.M.rem_euclid(360.0) * DEG, e);
+        let nu =
+            2.0 * ((1.0 + e).sqrt() * (ea / 2.0).sin()).atan2((1.0 - e).sqrt() * (ea / 2.0).cos());
+        let p = self.a * (1.0 - e * e);
+        let r = self.a * (1.0 - e * ea.cos());
+        let vf = (EARTH_MU / p).sqrt();
+
+        let pos_pf = [r * nu.cos(), r * nu.sin()];
+        let vel_pf = [-vf * nu.sin(), vf * (e + nu.cos())];
+
+        let (so, co) = (self.raan * DEG).sin_cos();
+        let (si, ci) = (self.inc * DEG).sin_cos();
+        let (sw, cw) = (self.argp * DEG).sin_cos();
+        // columns of the perifocal to inertial rotation matrix
+        let p_hat = [co * cw - so * sw * ci, so * cw + co * sw * ci, sw * si];
+        let q_hat = [-co * sw - so * cw * ci, -so * sw + co * cw * ci, cw * si];
+
+        let rotate = |v: [f64; 2]| {
+            [
+                p_hat[0] * v[0] + q_hat[0] * v[1],
+                p_hat[1] * v[0] + q_hat[1] * v[1],
+                p_hat[2] * v[0] + q_hat[2] * v[1],
+            ]
+        };
+
+        StateVector {
+            position: rotate(pos_pf),
+            velocity: rotate(vel_pf),
+        }
+    }
+}
+
+impl StateVector {
+    /// Convert the state vector to osculating Keplerian elements.
+    ///
+    /// For circular orbits the argument of perigee is set to zero and the mean anomaly
+    /// is measured from the ascending node; for equatorial orbits the right ascension
+    /// of the ascending node is set to zero.
+    pub fn to_keplerian(&self) -> Result<KeplerianElements, Error> {
+        const EPS: f64 = 1e-11;
+
+        let r = self.position;
+        let v = self.velocity;
+        let r_norm = norm(r);
+        let v2 = dot(v, v);
+
+        let energy = v2 / 2.0 - EARTH_MU / r_norm;
+        if r_norm == 0.0 || energy >= 0.0 {
+            return Err(Error::InvalidElements("orbit is not elliptic"));
+        }
+        let a = -EARTH_MU / (2.0 * energy);
+
+        let h = cross(r, v);
+        let h_norm = norm(h);
+        if h_norm == 0.0 {
+            return Err(Error::InvalidElements("orbit is degenerate"));
+        }
+        let node = [-h[1], h[0], 0.0];
+        let node_norm = norm(node);
+
+        let rv = dot(r, v);
+        let e_vec = [
+            ((v2 - EARTH_MU / r_norm) * r[0] - rv * v[0]) / EARTH_MU,
+            ((v2 - EARTH_MU / r_norm) * r[1] - rv * v[1]) / EARTH_MU,
+            ((v2 - EARTH_MU / r_norm) * r[2] - rv * v[2]) / EARTH_MU,
+        ];
+        let ecc = norm(e_vec);
+
+        let inc = (h[2] / h_norm).clamp(-1.0, 1.0).acos();
+        let equatorial = node_norm / h_norm < EPS;
+        let circular = ecc < EPS;
+
+        let raan = if equatorial {
+            0.0
+        } else {
+            node[1].atan2(node[0])
+        };
+        // reference direction in the orbital plane from which angles are measured
+        let (ref_dir, ref_perp) = if equatorial {
+            ([1.0, 0.0, 0.0], cross(h, [1.0, 0.0, 0.0]))
+        } else {
+            (node, cross(h, node))
+        };
+        let angle_from_ref = |x: [f64; 3]| dot(x, ref_perp).atan2(dot(x, ref_dir) * h_norm);
+
+        let (argp, nu) = if circular {
+            (0.0, angle_from_ref(r))
+        } else {
+            let argp = angle_from_ref(e_vec);
+            let nu = dot(cross(e_vec, r), h).atan2(dot(e_vec, r) * h_norm);
+            (argp, nu)
+        };
+
+        let ea = ((1.0 - ecc).sqrt() * (nu / 2.0).sin())
+            .atan2((1.0 + ecc).sqrt() * (nu / 2.0).cos())
+            * 2.0;
+        #[allow(non_snake_case)]
+        let M = ea - ecc * ea.sin();
+
+        Ok(KeplerianElements {
+            a,
+            ecc,
+            inc: inc / DEG,
+            raan: normalize_deg(raan / DEG),
+            argp: normalize_deg(argp / DEG),
+            M: normalize_deg(M / DEG),
+        })
+    }
+}
+
+impl TLE {
+    /// Semi-major axis (km) corresponding to the mean motion.
+    pub fn semi_major_axis(&self) -> f64 {
+        let n = self.n * TAU / 86400.0;
+        (EARTH_MU / (n * n)).cbrt()
+    }
+
+    /// Orbital period in minutes.
+    pub fn period(&self) -> f64 {
+        1440.0 / self.n
+    }
+
+    /// Altitude of the apogee above the equatorial radius (km).
+    pub fn apogee_altitude(&self) -> f64 {
+        self.semi_major_axis() * (1.0 + self.ecc) - EARTH_RADIUS
+    }
+
+    /// Altitude of the perigee above the equatorial radius (km).
+    pub fn perigee_altitude(&self) -> f64 {
+        self.semi_major_axis() * (1.0 - self.ecc) - EARTH_RADIUS
+    }
+}
+
+/// Equinoctial elements, non-singular for circular and equatorial orbits.
+#[cfg(feature = "sgp4")]
+#[derive(Clone, Copy)]
+struct Equinoctial([f64; 6]);
+
+#[cfg(feature = "sgp4")]
+impl Equinoctial {
+    fn from_keplerian(k: &KeplerianElements) -> Equinoctial {
+        let raan = k.raan * DEG;
+        let lon_peri = raan + k.argp * DEG;
+        let t = (k.inc * DEG / 2.0).tan();
+        Equinoctial([
+            k.a,
+            k.ecc * lon_peri.sin(),
+            k.ecc * lon_peri.cos(),
+            t * raan.sin(),
+            t * raan.cos(),
+            lon_peri + k.M * DEG,
+        ])
+    }
+
+    fn to_keplerian(self) -> KeplerianElements {
+        let [a, h, k, p, q, lambda] = self.0;
+        let lon_peri = h.atan2(k);
+        let raan = p.atan2(q);
+        KeplerianElements {
+            a,
+            ecc: h.hypot(k),
+            inc: 2.0 * p.hypot(q).atan() / DEG,
+            raan: normalize_deg(raan / DEG),
+            argp: normalize_deg((lon_peri - raan) / DEG),
+            M: normalize_deg((lambda - lon_peri) / DEG),
+        }
+    }
+}
+
+#[cfg(feature = "sgp4")]
+impl TLE {
+    /// Build a TLE whose SGP4 mean elements reproduce the given osculating elements at the epoch.
+    ///
+    /// The mean elements are found iteratively: starting from the osculating elements,
+    /// the TLE is propagated to its own epoch and corrected by the difference between
+    /// the target and the resulting osculating elements, until they agree.
+    ///
+    /// The catalog number is the placeholder `99999` and the international designator is
+    /// `int_desig`: with `None`, its columns are left blank. The drag terms are zero and
+    /// the classification is `U`.
+    pub fn from_keplerian(
+        elements: &KeplerianElements,
+        int_desig: Option<IntDesig>,
+        epoch_year: i32,
+        epoch_day: f64,
+    ) -> Result<TLE, Error> {
+        const MAX_ITERATIONS: usize = 50;
+        const TOLERANCE: f64 = 1e-6; // km
+
+        if !(0.0..1.0).contains(&elements.ecc) || elements.a <= 0.0 {
+            return Err(Error::InvalidElements("orbit is not elliptic"));
+        }
+
+        let target_state = elements.to_state_vector();
+        let target = Equinoctial::from_keplerian(elements);
+        let mut mean = target;
+
+        let mut tle = TLE {
+            norad: "99999".to_string(),
+            int_desig: int_desig.into(),
+            epoch_year,
+            epoch_day,
+            ..TLE::default()
+        };
+
+        for _ in 0..MAX_ITERATIONS {
+            tle.set_mean_elements(&mean.to_keplerian());
+            let state = tle.propagator()?.propagate(0.0)?;
+
+            let dr = [
+                state.position[0] - target_state.position[0],
+                state.position[1] - target_state.position[1],
+                state.position[2] - target_state.position[2],
+            ];
+            if norm(dr) < TOLERANCE {
+                return Ok(tle);
+            }
+
+            let osculating = Equinoctial::from_keplerian(&state.to_keplerian()?);
+            for i in 0..6 {
+                let mut delta = target.0[i] - osculating.0[i];
+                if i == 5 {
+                    delta = (delta + PI).rem_euclid(TAU) - PI;
+                }
+                mean.0[i] += delta;
+            }
+        }
+
+        Err(Error::InvalidElements("mean elements fit did not converge"))
+    }
+
+    /// Build a TLE reproducing the given state vector (TEME frame) at the epoch.
+    ///
+    /// See [`TLE::from_keplerian`].
+    pub fn from_state_vector(
+        state: &StateVector,
+        int_desig: Option<IntDesig>,
+        epoch_year: i32,
+        epoch_day: f64,
+    ) -> Result<TLE, Error> {
+        TLE::from_keplerian(&state.to_keplerian()?, int_desig, epoch_year, epoch_day)
+    }
+
+    /// Build a TLE from osculating elements at the given Julian date (UTC).
+    ///
+    /// The date must be in the years 1957 to 2056. See [`TLE::from_keplerian`].
+    pub fn from_keplerian_at(
+        elements: &KeplerianElements,
+        int_desig: Option<IntDesig>,
+        jd: f64,
+    ) -> Result<TLE, Error> {
+        let (epoch_year, epoch_day) =
+            year_and_day(jd).ok_or(Error::InvalidElements("epoch out of range [1957, 2056]"))?;
+        TLE::from_keplerian(elements, int_desig, epoch_year, epoch_day)
+    }
+
+    fn set_mean_elements(&mut self, elements: &KeplerianElements) {
+        self.inc = elements.inc;
+        self.raan = elements.raan;
+        self.ecc = elements.ecc;
+        self.argp = elements.argp;
+        self.M = elements.M;
+        self.n = elements.mean_motion();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64, tol: f64) {
+        assert!((a - b).abs() < tol, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_state_vector_round_trip() {
+        let elements = KeplerianElements {
+            a: 7000.0,
+            ecc: 0.01,
+            inc: 51.6,
+            raan: 120.0,
+            argp: 45.0,
+            M: 300.0,
+        };
+        let back = elements.to_state_vector().to_keplerian().unwrap();
+        assert_close(back.a, elements.a, 1e-6);
+        assert_close(back.ecc, elements.ecc, 1e-12);
+        assert_close(back.inc, elements.inc, 1e-9);
+        assert_close(back.raan, elements.raan, 1e-9);
+        assert_close(back.argp, elements.argp, 1e-7);
+        assert_close(back.M, elements.M, 1e-7);
+    }
+
+    #[test]
+    fn test_circular_orbit_speed() {
+        let elements = KeplerianElements {
+            a: 7000.0,
+            ..KeplerianElements::default()
+        };
+        let state = elements.to_state_vector();
+        assert_close(norm(state.position), 7000.0, 1e-9);
+        assert_close(norm(state.velocity), (EARTH_MU / 7000.0).sqrt(), 1e-12);
+    }
+
+    #[cfg(feature = "sgp4")]
+    #[test]
+    fn test_from_keplerian() {
+        let elements = KeplerianElements {
+            a: 6790.0,
+            ecc: 0.0005,
+            inc: 51.64,
+            raan: 242.0,
+            argp: 264.6,
+            M: 207.4,
+        };
+        let int_desig: IntDesig = "98067A".parse().unwrap();
+        let tle = TLE::from_keplerian(&elements, Some(int_desig), 2020, 45.5).unwrap();
+        let target = elements.to_state_vector();
+        let state = tle.propagator().unwrap().propagate(0.0).unwrap();
+        for i in 0..3 {
+            assert_close(state.position[i], target.position[i], 1e-5);
+            assert_close(state.velocity[i], target.velocity[i], 1e-7);
+        }
+
+        // the serialized TLE is valid, parses back and still reproduces the elements
+        let parsed = crate::parse(&tle.to_string()).unwrap();
+        assert_eq!(parsed.norad, "99999");
+        assert_eq!(parsed.int_desig.parsed(), Some(int_desig));
+        assert_close(parsed.n, tle.n, 1e-8);
+        // the mean and osculating elements differ by the short-period terms
+        assert_close(parsed.semi_major_axis(), elements.a, 20.0);
+        assert_close(parsed.n, elements.mean_motion(), 0.1);
+        assert_close(parsed.inc, elements.inc, 0.05);
+        assert_close(parsed.raan, elements.raan, 0.05);
+        let state = parsed.propagator().unwrap().propagate(0.0).unwrap();
+        for i in 0..3 {
+            assert_close(state.position[i], target.position[i], 0.1);
+            assert_close(state.velocity[i], target.velocity[i], 1e-4);
+        }
+
+        let blank = TLE::from_keplerian(&elements, None, 2020, 45.5).unwrap();
+        assert_eq!(blank.int_desig, crate::IntDesigField::Blank);
+        assert_eq!(&blank.line1()[9..17], "        ");
+
+        for jd in [f64::NAN, f64::INFINITY, 1e300, 0.0] {
+            assert!(matches!(
+                TLE::from_keplerian_at(&elements, None, jd),
+                Err(Error::InvalidElements(_))
+            ));
+        }
+        let tle = TLE::from_keplerian_at(&elements, None, crate::J2000).unwrap();
+        assert_eq!((tle.epoch_year, tle.epoch_day), (2000, 1.5));
+    }
+}
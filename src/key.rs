@@ -0,0 +1,179 @@
+use alloc::string::String;
+use core::cmp::Ordering;
+
+use crate::math::round;
+use crate::{ExactTLE, TLE};
+
+/// Canonical identity of an element set: object, epoch and element set number.
+///
+/// Keys are ordered by object, then by epoch and finally by element set number,
+/// so that sorting a catalog by key groups the element sets of each object
+/// chronologically. Two element sets with the same key are considered duplicates.
+///
+/// The objects are ordered by catalog number: numerically, with or without
+/// zero padding, and with the Alpha-5 numbers (`A0000` is 100000) after the
+/// numeric ones. Norad numbers in neither form come last, in lexicographic order.
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// let iss = "ISS (ZARYA)
+/// 1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+/// 2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+/// let tles = vec![tletools::parse(iss).unwrap(), tletools::parse(iss).unwrap()];
+/// let mut seen = HashSet::new();
+/// let unique: Vec<_> = tles.into_iter().filter(|tle| seen.insert(tle.key())).collect();
+/// assert_eq!(unique.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TLEKey {
+    /// Norad number.
+    pub norad: String,
+    /// Year of the epoch.
+    pub epoch_year: i32,
+    /// Day of the year plus fraction of the day, in units of 10⁻⁸ day.
+    pub epoch_day: u64,
+    /// Element set number.
+    pub set_num: u32,
+}
+
+/// Catalog number of a Norad number in the numeric or in the Alpha-5 form.
+fn catalog_number(norad: &str) -> Option<u32> {
+    let norad = norad.trim().as_bytes();
+    match norad {
+        [] => None,
+        _ if norad.len() <= 5 && norad.iter().all(u8::is_ascii_digit) => {
+            Some(norad.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+        }
+        [c @ b'A'..=b'Z', digits @ ..]
+            if digits.len() == 4
+                && !matches!(c, b'I' | b'O')
+                && digits.iter().all(u8::is_ascii_digit) =>
+        {
+            // A is 10, and I and O are skipped
+            let letter = u32::from(c - b'A') + 10 - u32::from(*c > b'I') - u32::from(*c > b'O');
+            let digits = digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0'));
+            Some(letter * 10_000 + digits)
+        }
+        _ => None,
+    }
+}
+
+/// Sort key of a Norad number: the catalog number, then the text to keep the order total.
+pub(crate) fn norad_order(norad: &str) -> (u32, &str) {
+    (catalog_number(norad).unwrap_or(u32::MAX), norad)
+}
+
+impl Ord for TLEKey {
+    fn cmp(&self, other: &TLEKey) -> Ordering {
+        norad_order(&self.norad)
+            .cmp(&norad_order(&other.norad))
+            .then(self.epoch_year.cmp(&other.epoch_year))
+            .then(self.epoch_day.cmp(&other.epoch_day))
+            .then(self.set_num.cmp(&other.set_num))
+    }
+}
+
+impl PartialOrd for TLEKey {
+    fn partial_cmp(&self, other: &TLEKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TLE {
+    /// Canonical identity of the element set.
+    ///
+    /// The epoch is rounded to the precision of the TLE format (10⁻⁸ day).
+    pub fn key(&self) -> TLEKey {
+        TLEKey {
+            norad: self.norad.clone(),
+            epoch_year: self.epoch_year,
+            epoch_day: round(self.epoch_day * 1e8) as u64,
+            set_num: self.set_num,
+        }
+    }
+}
+
+impl ExactTLE {
+    /// Canonical identity of the element set.
+    pub fn key(&self) -> TLEKey {
+        TLEKey {
+            norad: self.norad.clone(),
+            epoch_year: self.epoch_year,
+            epoch_day: self.epoch_day,
+            set_num: self.set_num,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_key() {
+        let tle = crate::parse(ISS).unwrap();
+        let key = tle.key();
+        assert_eq!(key.epoch_day, 4_518_587_073);
+        assert_eq!(key.set_num, 999);
+
+        let mut lines = ISS.lines();
+        let exact = ExactTLE::from_lines(
+            lines.next().unwrap(),
+            lines.next().unwrap(),
+            lines.next().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(exact.key(), key);
+    }
+
+    #[test]
+    fn test_dedup_and_order() {
+        let mut later = crate::parse(ISS).unwrap();
+        later.epoch_day += 0.5;
+        let mut other = crate::parse(ISS).unwrap();
+        other.norad = "00005".into();
+
+        let tles = [
+            later,
+            crate::parse(ISS).unwrap(),
+            other,
+            crate::parse(ISS).unwrap(),
+        ];
+        let keys: HashSet<TLEKey> = tles.iter().map(TLE::key).collect();
+        assert_eq!(keys.len(), 3);
+
+        let mut sorted: Vec<TLEKey> = keys.into_iter().collect();
+        sorted.sort();
+        assert_eq!(sorted[0].norad, "00005");
+        assert!(sorted[1].epoch_day < sorted[2].epoch_day);
+    }
+
+    #[test]
+    fn test_catalog_order() {
+        assert_eq!(catalog_number("25544"), Some(25544));
+        assert_eq!(catalog_number("    5"), Some(5));
+        assert_eq!(catalog_number("A0000"), Some(100_000));
+        assert_eq!(catalog_number("J0001"), Some(180_001));
+        assert_eq!(catalog_number("Z9999"), Some(339_999));
+        for norad in ["", "I0000", "a0000", "AB123", "123456"] {
+            assert_eq!(catalog_number(norad), None);
+        }
+
+        let key = |norad: &str| TLEKey {
+            norad: norad.into(),
+            epoch_year: 2020,
+            epoch_day: 0,
+            set_num: 0,
+        };
+        let mut keys = ["?", "A0000", "99999", "10", "9", "00009"].map(key);
+        keys.sort();
+        let sorted: Vec<&str> = keys.iter().map(|k| k.norad.as_str()).collect();
+        assert_eq!(sorted, ["00009", "9", "10", "99999", "A0000", "?"]);
+    }
+}
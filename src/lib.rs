@@ -1,4 +1,5 @@
-#![cfg_attr(doc, feature(doc_auto_cfg))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 
 //! **TLE-tools** is a small library to work with [`two-line element set`] files.
 //!
@@ -6,6 +7,17 @@
 //! Its fields are expressed in the same units that are used by the TLE format.
 //!
 //! [`two-line element set`]: https://en.wikipedia.org/wiki/Two-line_element_set
+//!
+//! # `no_std`
+//!
+//! Parsing and formatting only need `core` and `alloc`: disable the default
+//! `std` feature to use the crate on targets without an operating system.
+//! Reading from `std::io` sources, the Keplerian conversions, the propagation
+//! and the CSV and OEM support require `std`.
+
+extern crate alloc;
+
+use alloc::string::String;
 
 /// Structure representing a single TLE.
 ///
@@ -21,10 +33,10 @@ pub struct TLE {
     pub name: String,
     /// Norad number.
     pub norad: String,
-    /// Classification (`U`: unclassified, `C`: classified, `S`: secret)
-    pub classification: char,
-    /// International designator (year, launch number, piece).
-    pub int_desig: String,
+    /// Classification.
+    pub classification: Classification,
+    /// International designator (year, launch number, piece), if any.
+    pub int_desig: IntDesigField,
     /// Year of the epoch.
     pub epoch_year: i32,
     /// Day of the year plus fraction of the day.
@@ -35,6 +47,8 @@ pub struct TLE {
     pub ddn_o6: f64,
     /// BSTAR coefficient ([Wikipedia](https://en.wikipedia.org/wiki/BSTAR)).
     pub bstar: f64,
+    /// Ephemeris type (orbital model).
+    pub ephem_type: EphemerisType,
     /// Element set number.
     pub set_num: u32,
     /// Inclination.
@@ -54,8 +68,76 @@ pub struct TLE {
     pub rev_num: u32,
 }
 
+mod digits;
+mod math;
+
+#[cfg(test)]
+mod strategy;
+
 mod implem;
 pub use implem::*;
 
+mod epoch;
+pub use epoch::J2000;
+
+mod format;
+pub use format::*;
+pub mod fast;
+
+mod exact;
+pub use exact::*;
+
+mod key;
+pub use key::*;
+mod builder;
+pub use builder::*;
+
+pub mod binary;
+
+mod fields;
+pub use fields::*;
+
+mod int_desig;
+pub use int_desig::*;
+
+mod name;
+pub use name::*;
+
+#[cfg(feature = "std")]
+pub mod reader;
+#[cfg(feature = "tokio")]
+pub use reader::AsyncReader;
+
+#[cfg(feature = "std")]
+pub mod compressed;
+#[cfg(feature = "std")]
+pub use reader::{parse_all, Reader};
+
+#[cfg(feature = "std")]
+mod kepler;
+#[cfg(feature = "std")]
+pub use kepler::*;
+
+#[cfg(feature = "std")]
+pub mod validate;
+
+#[cfg(feature = "std")]
+pub mod diff;
+
+#[cfg(feature = "sgp4")]
+mod propagate;
+#[cfg(feature = "sgp4")]
+pub use propagate::*;
+
+#[cfg(feature = "sgp4")]
+pub mod batch;
+#[cfg(feature = "sgp4")]
+pub mod eclipse;
+#[cfg(feature = "sgp4")]
+pub mod oem;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+
 #[cfg(feature = "nom")]
 pub mod nom;
@@ -0,0 +1,67 @@
+//! Floating-point helpers that do not require `std`.
+//!
+//! `core` does not provide `f64::powi` and `f64::round`, so the parsing and
+//! formatting code uses these instead.
+
+/// Power of ten, exact for `0 ≤ exp ≤ 22`.
+fn pow10(exp: u32) -> f64 {
+    (0..exp).fold(1.0, |acc, _| acc * 10.0)
+}
+
+/// Compute `m × 10^exp`.
+///
+/// Negative exponents divide by the exact power of ten, so that the result
+/// is the `f64` closest to the decimal value.
+pub(crate) fn scale10(m: f64, exp: i32) -> f64 {
+    if exp < 0 {
+        m / pow10(exp.unsigned_abs())
+    } else {
+        m * pow10(exp as u32)
+    }
+}
+
+/// Round to the nearest integer, rounding half-way cases away from zero.
+///
+/// Only meant for values whose magnitude is below `2^63`.
+pub(crate) fn round(x: f64) -> f64 {
+    let t = x as i64 as f64;
+    let d = x - t;
+    if d >= 0.5 {
+        t + 1.0
+    } else if d <= -0.5 {
+        t - 1.0
+    } else {
+        t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale10() {
+        assert_eq!(scale10(25302.0, -9), 2.5302e-5);
+        assert_eq!(scale10(-11606.0, -9), -1.1606e-5);
+        assert_eq!(scale10(12345.0, 0), 12345.0);
+        assert_eq!(scale10(3.0, 2), 300.0);
+    }
+
+    #[test]
+    fn test_round() {
+        for x in [
+            0.0,
+            0.4,
+            0.5,
+            1.49,
+            2.5,
+            -0.5,
+            -1.4,
+            -2.5,
+            4885.000001,
+            1e15 + 0.5,
+        ] {
+            assert_eq!(round(x), x.round(), "{}", x);
+        }
+    }
+}
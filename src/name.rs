@@ -0,0 +1,188 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+
+use crate::TLE;
+
+/// Operational status of a satellite, as annotated by CelesTrak with a `[c]` suffix on the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationalStatus {
+    /// `+`: operational.
+    Operational,
+    /// `-`: nonoperational.
+    Nonoperational,
+    /// `P`: partially operational.
+    PartiallyOperational,
+    /// `B`: backup or standby.
+    Backup,
+    /// `S`: spare.
+    Spare,
+    /// `X`: extended mission.
+    ExtendedMission,
+    /// `D`: decayed.
+    Decayed,
+    /// `?`: unknown.
+    Unknown,
+}
+
+impl OperationalStatus {
+    /// Status corresponding to the given code, if any.
+    pub fn from_code(c: char) -> Option<OperationalStatus> {
+        use OperationalStatus::*;
+        Some(match c {
+            '+' => Operational,
+            '-' => Nonoperational,
+            'P' => PartiallyOperational,
+            'B' => Backup,
+            'S' => Spare,
+            'X' => ExtendedMission,
+            'D' => Decayed,
+            '?' => Unknown,
+            _ => return None,
+        })
+    }
+
+    /// Code of the status.
+    pub fn code(self) -> char {
+        use OperationalStatus::*;
+        match self {
+            Operational => '+',
+            Nonoperational => '-',
+            PartiallyOperational => 'P',
+            Backup => 'B',
+            Spare => 'S',
+            ExtendedMission => 'X',
+            Decayed => 'D',
+            Unknown => '?',
+        }
+    }
+}
+
+/// Type of object, as deduced from the conventional words in its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    /// Rocket body (`R/B`).
+    RocketBody,
+    /// Debris (`DEB`).
+    Debris,
+}
+
+/// Metadata extracted from the name of an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameMetadata<'a> {
+    /// Name without the status annotation.
+    pub name: &'a str,
+    /// Operational status, if annotated.
+    pub status: Option<OperationalStatus>,
+    /// Type of object, if it can be deduced from the name.
+    pub object_type: Option<ObjectType>,
+}
+
+impl<'a> NameMetadata<'a> {
+    /// Extract the metadata from a name (without the Space-Track `0 ` prefix).
+    pub fn parse(name: &'a str) -> NameMetadata<'a> {
+        let name = name.trim();
+
+        let mut status = None;
+        let mut base = name;
+        if let Some(rest) = name.strip_suffix(']') {
+            let mut chars = rest.chars().rev();
+            if let (Some(c), Some('[')) = (chars.next(), chars.next()) {
+                if let Some(s) = OperationalStatus::from_code(c) {
+                    status = Some(s);
+                    base = rest[..rest.len() - c.len_utf8() - 1].trim_end();
+                }
+            }
+        }
+
+        let object_type = base.split_whitespace().find_map(|word| match word {
+            "R/B" => Some(ObjectType::RocketBody),
+            "DEB" => Some(ObjectType::Debris),
+            _ => None,
+        });
+
+        NameMetadata {
+            name: base,
+            status,
+            object_type,
+        }
+    }
+}
+
+/// Decode a name line: UTF-8 if valid, otherwise Latin-1 (ISO 8859-1).
+///
+/// Every byte is a valid Latin-1 character, so decoding never fails, although
+/// names in other 8-bit encodings come out wrong.
+pub(crate) fn decode_name(bytes: &[u8]) -> Cow<'_, str> {
+    match core::str::from_utf8(bytes) {
+        Ok(name) => Cow::Borrowed(name),
+        Err(_) => Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect()),
+    }
+}
+
+/// Trim the name line and remove the Space-Track `0 ` prefix.
+pub(crate) fn normalize_name(name_line: &str) -> String {
+    let name = name_line.trim();
+    name.strip_prefix("0 ")
+        .unwrap_or(name)
+        .trim_start()
+        .to_string()
+}
+
+impl TLE {
+    /// Metadata contained in the name of the object.
+    pub fn name_metadata(&self) -> NameMetadata<'_> {
+        NameMetadata::parse(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_metadata() {
+        let meta = NameMetadata::parse("ISS (ZARYA)");
+        assert_eq!(meta.name, "ISS (ZARYA)");
+        assert_eq!(meta.status, None);
+        assert_eq!(meta.object_type, None);
+
+        let meta = NameMetadata::parse("GPS BIIR-2  (PRN 13)    [+]");
+        assert_eq!(meta.name, "GPS BIIR-2  (PRN 13)");
+        assert_eq!(meta.status, Some(OperationalStatus::Operational));
+
+        let meta = NameMetadata::parse("FENGYUN 1C DEB [-]");
+        assert_eq!(meta.name, "FENGYUN 1C DEB");
+        assert_eq!(meta.status, Some(OperationalStatus::Nonoperational));
+        assert_eq!(meta.object_type, Some(ObjectType::Debris));
+
+        let meta = NameMetadata::parse("CZ-4B R/B");
+        assert_eq!(meta.object_type, Some(ObjectType::RocketBody));
+
+        let meta = NameMetadata::parse("WEIRD [Q]");
+        assert_eq!(meta.name, "WEIRD [Q]");
+        assert_eq!(meta.status, None);
+    }
+
+    #[test]
+    fn test_decode_name() {
+        assert_eq!(decode_name("SÃO PAULO".as_bytes()), "SÃO PAULO");
+        assert_eq!(decode_name(b"S\xc3O PAULO"), "SÃO PAULO");
+        assert_eq!(decode_name(b"\xff\x80"), "\u{ff}\u{80}");
+    }
+
+    #[test]
+    fn test_space_track_prefix() {
+        let tle = crate::from_lines(
+            "0 VANGUARD 1 [-]",
+            "1 00005U 58002B   20045.59766236  .00000028  00000-0  39591-4 0  9995",
+            "2 00005  34.2467 209.5404 1846039 214.0436 132.2098 10.84868778205428",
+        )
+        .unwrap();
+        assert_eq!(tle.name, "VANGUARD 1 [-]");
+        assert_eq!(tle.name_metadata().name, "VANGUARD 1");
+        assert_eq!(
+            tle.name_metadata().status,
+            Some(OperationalStatus::Nonoperational)
+        );
+    }
+}
@@ -1,15 +1,17 @@
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take, take_while_m_n};
-use nom::character::complete::{char, line_ending, not_line_ending};
-use nom::character::complete::{u32, u8};
-use nom::character::is_digit;
-use nom::combinator::{all_consuming, map, map_parser, opt};
-use nom::sequence::separated_pair;
+use nom::character::complete::{char, line_ending};
+use nom::combinator::{all_consuming, map, map_opt, map_res, opt};
 use nom::IResult;
 
+use alloc::string::ToString;
+
 use thiserror::Error;
 
-use crate::TLE;
+use crate::digits::{epoch_day, u8_2_digits};
+use crate::epoch::full_year;
+use crate::name::normalize_name;
+use crate::{Classification, IntDesigField, TLE};
 
 #[derive(Debug, Error)]
 #[error("invalid TLE string")]
@@ -22,7 +24,7 @@ impl From<nom::Err<nom::error::Error<&str>>> for Error {
 }
 
 #[cfg(feature = "nom")]
-impl std::str::FromStr for TLE {
+impl core::str::FromStr for TLE {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<TLE, Error> {
@@ -39,9 +41,9 @@ pub fn parse_single_tle(s: &str) -> IResult<&str, TLE> {
     Ok((
         rest,
         TLE {
-            name: line_0.to_string(),
+            name: normalize_name(line_0),
             norad: norad.to_string(),
-            int_desig: int_desig.to_string(),
+            int_desig,
             classification,
             epoch_year,
             epoch_day,
@@ -60,115 +62,26 @@ fn segment_lines(s: &str) -> IResult<&str, (&str, &str, &str)> {
     Ok((s, (line_0, line_1, line_2)))
 }
 
-fn parse_line_1(s: &str) -> IResult<&str, (&str, &str, char, i32, f64)> {
+fn parse_line_1(s: &str) -> IResult<&str, (&str, IntDesigField, Classification, i32, f64)> {
     let (s, _) = tag("1 ")(s)?;
     let (s, norad) = take(5usize)(s)?;
-    let (s, classification) = alt((char('C'), char('U'), char('S')))(s)?;
+    let (s, classification) = map_res(alt((char('C'), char('U'), char('S'))), |c| {
+        Classification::try_from(c)
+    })(s)?;
     let (s, _) = char(' ')(s)?;
-    let (s, int_desig) = take(8usize)(s)?;
+    let (s, int_desig) = map(take(8usize), |s: &str| {
+        IntDesigField::from_columns(s.as_bytes())
+    })(s)?;
     let (s, _) = char(' ')(s)?;
-    let (s, y) = map_parser(
-        take_while_m_n(2usize, 2usize, |c: char| c.is_digit(10)),
-        all_consuming(u8),
-    )(s)?;
-    let epoch_year = if y <= 56 {
-        2000 + y as i32
-    } else {
-        1900 + y as i32
-    };
-    let (s, epoch_day) = map(
-        separated_pair(
-            map_parser(take_digits(3), u32),
-            char('.'),
-            map_parser(take_digits(8), u32),
-        ),
-        |(i, f)| i as f64 + (f as f64) / 100000000.,
-    )(s)?;
+    let (s, y) = map_opt(take(2usize), |s: &str| u8_2_digits(s.as_bytes()))(s)?;
+    let epoch_year = full_year(y);
+    let (s, epoch_day) = map_opt(take(12usize), |s: &str| epoch_day(s.as_bytes()))(s)?;
     Ok((s, (norad, int_desig, classification, epoch_year, epoch_day)))
 }
 
-fn tle_name(s: &str) -> IResult<&str, &str> {
-    not_line_ending(s)
-}
-
-fn take_digits<I>(count: usize) -> impl Fn(I) -> IResult<I, I>
-where
-    I: nom::InputIter<Item = char>
-        + nom::InputTake
-        + nom::InputLength
-        + nom::Slice<std::ops::RangeFrom<usize>>,
-{
-    take_while_m_n(count, count, |c: char| c.is_digit(10))
-}
-
-fn f64_3_4_digits(s: &[u8]) -> IResult<&[u8], f64> {
-    let (s, (a, b)) = separated_pair(u16_3_digits, tag("."), u16_4_digits)(s)?;
-    let x = a as f64 + b as f64 / 10000.;
-    Ok((s, x))
-}
-
-fn u8_2_digits(s: &[u8]) -> IResult<&[u8], u8> {
-    let (s, digits) = take_while_m_n(2usize, 2usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u8 * 10 + (digits[1] - b'0') as u8;
-    Ok((s, n))
-}
-
-fn u16_3_digits(s: &[u8]) -> IResult<&[u8], u16> {
-    let (s, digits) = take_while_m_n(3usize, 3usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u16 * 100
-        + (digits[1] - b'0') as u16 * 10
-        + (digits[2] - b'0') as u16;
-    Ok((s, n))
-}
-
-fn u16_4_digits(s: &[u8]) -> IResult<&[u8], u16> {
-    let (s, digits) = take_while_m_n(4usize, 4usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u16 * 1000
-        + (digits[1] - b'0') as u16 * 100
-        + (digits[2] - b'0') as u16 * 10
-        + (digits[3] - b'0') as u16;
-    Ok((s, n))
-}
-
-fn u32_5_digits(s: &[u8]) -> IResult<&[u8], u32> {
-    let (s, digits) = take_while_m_n(5usize, 5usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u32 * 10000
-        + (digits[1] - b'0') as u32 * 1000
-        + (digits[2] - b'0') as u32 * 100
-        + (digits[3] - b'0') as u32 * 10
-        + (digits[4] - b'0') as u32;
-    Ok((s, n))
-}
-
-fn u32_7_digits(s: &[u8]) -> IResult<&[u8], u32> {
-    let (s, digits) = take_while_m_n(7usize, 7usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u32 * 1000000
-        + (digits[1] - b'0') as u32 * 100000
-        + (digits[2] - b'0') as u32 * 10000
-        + (digits[3] - b'0') as u32 * 1000
-        + (digits[4] - b'0') as u32 * 100
-        + (digits[5] - b'0') as u32 * 10
-        + (digits[6] - b'0') as u32;
-    Ok((s, n))
-}
-
-fn u32_8_digits(s: &[u8]) -> IResult<&[u8], u32> {
-    let (s, digits) = take_while_m_n(8usize, 8usize, is_digit)(s)?;
-    let n = (digits[0] - b'0') as u32 * 10000000
-        + (digits[1] - b'0') as u32 * 1000000
-        + (digits[2] - b'0') as u32 * 100000
-        + (digits[3] - b'0') as u32 * 10000
-        + (digits[4] - b'0') as u32 * 1000
-        + (digits[5] - b'0') as u32 * 100
-        + (digits[6] - b'0') as u32 * 10
-        + (digits[7] - b'0') as u32;
-    Ok((s, n))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
 
     use nom::error::{Error, ErrorKind};
 
@@ -177,129 +90,6 @@ mod tests {
     // 2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791
     // ";
 
-    #[test]
-    fn test_f64_3_4_digits() {
-        const N: i32 = 10000;
-        for n in 0..N {
-            let x = n as f64 / N as f64;
-            let string = format!("{:08.4}", x);
-            let s = string.as_bytes();
-            match f64_3_4_digits(s) {
-                Ok((s, y)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(y, x);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    fn test_u8_2_digits() {
-        for n in 0..=99 {
-            let string = format!("{:02}", n);
-            let s = string.as_bytes();
-            match u8_2_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    fn test_u16_3_digits() {
-        for n in 0..=999 {
-            let string = format!("{:03}", n);
-            let s = string.as_bytes();
-            match u16_3_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    fn test_u16_4_digits() {
-        for n in 0..=9999 {
-            let string = format!("{:04}", n);
-            let s = string.as_bytes();
-            match u16_4_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    fn test_u32_5_digits() {
-        for n in 0..=99999 {
-            let string = format!("{:05}", n);
-            let s = string.as_bytes();
-            match u32_5_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn test_u32_7_digits() {
-        for n in (0..=9999999).skip(7) {
-            let string = format!("{:07}", n);
-            let s = string.as_bytes();
-            match u32_7_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
-    #[test]
-    #[ignore]
-    fn test_u32_8_digits() {
-        for n in (0..=99999999).skip(71) {
-            let string = format!("{:08}", n);
-            let s = string.as_bytes();
-            match u32_8_digits(s) {
-                Ok((s, m)) => {
-                    assert_eq!(s, b"");
-                    assert_eq!(m, n);
-                }
-                e => {
-                    panic!("parser returned {e:?}");
-                }
-            }
-        }
-    }
-
     #[test]
     fn test_segment_lines() {
         let line_0 = "ISS (ZARYA)";
@@ -365,19 +155,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn parse_tle_name() {
-        let tle_string = "ISS (ZARYA)
-1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
-2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791
-";
-        let rest = "
-1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
-2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791
-";
-        assert_eq!(tle_name(tle_string), Ok((rest, "ISS (ZARYA)")));
-    }
-
     #[test]
     fn test_from_str() {
         let tle_string = "ISS (ZARYA)
@@ -389,7 +166,7 @@ mod tests {
         let (s, tle) = result.expect("cannot parse TLE");
         assert_eq!(s, "");
         assert_eq!(tle.name, "ISS (ZARYA)");
-        assert_eq!(tle.classification, 'U');
+        assert_eq!(tle.classification, Classification::Unclassified);
         assert_eq!(tle.epoch_year, 2020);
         assert_eq!(tle.epoch_day, 045.18587073);
     }
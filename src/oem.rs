@@ -0,0 +1,379 @@
+//! Export of SGP4 ephemerides as CCSDS Orbit Ephemeris Messages (OEM).
+//!
+//! The states are propagated from a [`TLE`] and written either in the
+//! Keyword = Value Notation (KVN) or in the XML format described by the
+//! [CCSDS 502.0-B-2](https://public.ccsds.org/Pubs/502x0b2c1e2.pdf) standard.
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+use crate::epoch::iso8601;
+use crate::TLE;
+
+/// Largest number of states of an ephemeris, which are all computed before being written.
+pub const MAX_RECORDS: usize = 1_000_000;
+
+/// Encoding of the OEM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OemFormat {
+    /// Keyword = Value Notation.
+    #[default]
+    Kvn,
+    /// XML.
+    Xml,
+}
+
+#[derive(Error, Debug)]
+pub enum OemError {
+    #[error("I/O error")]
+    IoError(#[from] io::Error),
+    #[error("invalid time span, reason: {0}")]
+    InvalidTimeSpan(&'static str),
+    #[error(transparent)]
+    PropagationError(#[from] crate::PropagationError),
+}
+
+/// Options controlling the generation of an OEM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemOptions {
+    /// First state of the ephemeris (Julian date, UTC).
+    pub start: f64,
+    /// Last state of the ephemeris (Julian date, UTC).
+    pub stop: f64,
+    /// Step between consecutive states (seconds).
+    pub step: f64,
+    /// Encoding of the message.
+    pub format: OemFormat,
+    /// Value of the `ORIGINATOR` header keyword.
+    pub originator: String,
+    /// Value of the `CREATION_DATE` header keyword (Julian date, UTC); the current time if `None`.
+    pub creation_date: Option<f64>,
+}
+
+impl OemOptions {
+    /// Options for an ephemeris from `start` to `stop` (Julian dates, UTC) every `step` seconds.
+    pub fn new(start: f64, stop: f64, step: f64) -> OemOptions {
+        OemOptions {
+            start,
+            stop,
+            step,
+            format: OemFormat::Kvn,
+            originator: "TLETOOLS".to_string(),
+            creation_date: None,
+        }
+    }
+}
+
+/// A single state of the ephemeris.
+struct Record {
+    epoch: String,
+    position: [f64; 3],
+    velocity: [f64; 3],
+}
+
+/// Metadata of the single segment of the OEM.
+struct Metadata {
+    object_name: String,
+    object_id: String,
+    start: String,
+    stop: String,
+}
+
+impl TLE {
+    /// Propagate the TLE over the time span in `options` and write the resulting OEM.
+    ///
+    /// The states are expressed in the TEME frame, which is the native frame of SGP4.
+    /// The time span may contain at most [`MAX_RECORDS`] states.
+    pub fn write_oem<W: Write>(&self, mut writer: W, options: &OemOptions) -> Result<(), OemError> {
+        if options.step.is_nan() || options.step <= 0.0 {
+            return Err(OemError::InvalidTimeSpan("step must be positive"));
+        }
+        if !options.start.is_finite() || !options.stop.is_finite() {
+            return Err(OemError::InvalidTimeSpan("start and stop must be finite"));
+        }
+        if options.stop < options.start {
+            return Err(OemError::InvalidTimeSpan("stop precedes start"));
+        }
+
+        let count = ((options.stop - options.start) * 86400.0 / options.step + 1e-9).floor();
+        if count >= MAX_RECORDS as f64 {
+            return Err(OemError::InvalidTimeSpan("step is too short"));
+        }
+        let count = count as usize;
+
+        let propagator = self.propagator()?;
+        let records = (0..=count)
+            .map(|k| {
+                let jd = options.start + k as f64 * options.step / 86400.0;
+                let state = propagator.propagate_to(jd)?;
+                Ok(Record {
+                    epoch: iso8601(jd),
+                    position: state.position,
+                    velocity: state.velocity,
+                })
+            })
+            .collect::<Result<Vec<_>, crate::PropagationError>>()?;
+
+        let creation_date = options.creation_date.unwrap_or_else(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64());
+            now / 86400.0 + 2_440_587.5
+        });
+        let metadata = Metadata {
+            object_name: if self.name.is_empty() {
+                self.norad.clone()
+            } else {
+                self.name.clone()
+            },
+            object_id: self
+                .int_desig
+                .parsed()
+                .map_or_else(|| self.norad.clone(), |d| d.cospar()),
+            start: records[0].epoch.clone(),
+            stop: records[records.len() - 1].epoch.clone(),
+        };
+
+        match options.format {
+            OemFormat::Kvn => write_kvn(
+                &mut writer,
+                &iso8601(creation_date),
+                &options.originator,
+                &metadata,
+                &records,
+            )?,
+            OemFormat::Xml => write_xml(
+                &mut writer,
+                &iso8601(creation_date),
+                &options.originator,
+                &metadata,
+                &records,
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Propagate the TLE over the time span in `options` and return the resulting OEM.
+    pub fn to_oem(&self, options: &OemOptions) -> Result<String, OemError> {
+        let mut buf = Vec::new();
+        self.write_oem(&mut buf, options)?;
+        Ok(String::from_utf8(buf).expect("OEM is valid UTF-8"))
+    }
+}
+
+/// A KVN value is the rest of its line: line breaks are replaced with spaces.
+fn escape_kvn(s: &str) -> String {
+    s.replace(['\n', '\r'], " ")
+}
+
+fn write_kvn<W: Write>(
+    w: &mut W,
+    creation_date: &str,
+    originator: &str,
+    metadata: &Metadata,
+    records: &[Record],
+) -> io::Result<()> {
+    writeln!(w, "CCSDS_OEM_VERS = 2.0")?;
+    writeln!(w, "CREATION_DATE = {}", creation_date)?;
+    writeln!(w, "ORIGINATOR = {}", escape_kvn(originator))?;
+    writeln!(w)?;
+    writeln!(w, "META_START")?;
+    writeln!(w, "OBJECT_NAME = {}", escape_kvn(&metadata.object_name))?;
+    writeln!(w, "OBJECT_ID = {}", escape_kvn(&metadata.object_id))?;
+    writeln!(w, "CENTER_NAME = EARTH")?;
+    writeln!(w, "REF_FRAME = TEME")?;
+    writeln!(w, "TIME_SYSTEM = UTC")?;
+    writeln!(w, "START_TIME = {}", metadata.start)?;
+    writeln!(w, "STOP_TIME = {}", metadata.stop)?;
+    writeln!(w, "META_STOP")?;
+    writeln!(w)?;
+    for r in records {
+        writeln!(
+            w,
+            "{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}",
+            r.epoch,
+            r.position[0],
+            r.position[1],
+            r.position[2],
+            r.velocity[0],
+            r.velocity[1],
+            r.velocity[2]
+        )?;
+    }
+    Ok(())
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn write_xml<W: Write>(
+    w: &mut W,
+    creation_date: &str,
+    originator: &str,
+    metadata: &Metadata,
+    records: &[Record],
+) -> io::Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<oem xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="https://sanaregistry.org/r/ndmxml_unqualified/ndmxml-2.0.0-master-2.0.xsd" id="CCSDS_OEM_VERS" version="2.0">"#
+    )?;
+    writeln!(w, "  <header>")?;
+    writeln!(w, "    <CREATION_DATE>{}</CREATION_DATE>", creation_date)?;
+    writeln!(w, "    <ORIGINATOR>{}</ORIGINATOR>", escape_xml(originator))?;
+    writeln!(w, "  </header>")?;
+    writeln!(w, "  <body>")?;
+    writeln!(w, "    <segment>")?;
+    writeln!(w, "      <metadata>")?;
+    writeln!(
+        w,
+        "        <OBJECT_NAME>{}</OBJECT_NAME>",
+        escape_xml(&metadata.object_name)
+    )?;
+    writeln!(
+        w,
+        "        <OBJECT_ID>{}</OBJECT_ID>",
+        escape_xml(&metadata.object_id)
+    )?;
+    writeln!(w, "        <CENTER_NAME>EARTH</CENTER_NAME>")?;
+    writeln!(w, "        <REF_FRAME>TEME</REF_FRAME>")?;
+    writeln!(w, "        <TIME_SYSTEM>UTC</TIME_SYSTEM>")?;
+    writeln!(w, "        <START_TIME>{}</START_TIME>", metadata.start)?;
+    writeln!(w, "        <STOP_TIME>{}</STOP_TIME>", metadata.stop)?;
+    writeln!(w, "      </metadata>")?;
+    writeln!(w, "      <data>")?;
+    for r in records {
+        writeln!(w, "        <stateVector>")?;
+        writeln!(w, "          <EPOCH>{}</EPOCH>", r.epoch)?;
+        writeln!(w, "          <X>{:.6}</X>", r.position[0])?;
+        writeln!(w, "          <Y>{:.6}</Y>", r.position[1])?;
+        writeln!(w, "          <Z>{:.6}</Z>", r.position[2])?;
+        writeln!(w, "          <X_DOT>{:.9}</X_DOT>", r.velocity[0])?;
+        writeln!(w, "          <Y_DOT>{:.9}</Y_DOT>", r.velocity[1])?;
+        writeln!(w, "          <Z_DOT>{:.9}</Z_DOT>", r.velocity[2])?;
+        writeln!(w, "        </stateVector>")?;
+    }
+    writeln!(w, "      </data>")?;
+    writeln!(w, "    </segment>")?;
+    writeln!(w, "  </body>")?;
+    writeln!(w, "</oem>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    fn options(format: OemFormat) -> (TLE, OemOptions) {
+        let tle = crate::parse(ISS).unwrap();
+        let start = tle.epoch_julian_date();
+        let mut options = OemOptions::new(start, start + 10.0 / 1440.0, 60.0);
+        options.format = format;
+        options.creation_date = Some(crate::J2000);
+        (tle, options)
+    }
+
+    #[test]
+    fn test_kvn() {
+        let (tle, options) = options(OemFormat::Kvn);
+        let oem = tle.to_oem(&options).unwrap();
+        let lines: Vec<&str> = oem.lines().collect();
+
+        assert_eq!(lines[0], "CCSDS_OEM_VERS = 2.0");
+        assert_eq!(lines[1], "CREATION_DATE = 2000-01-01T12:00:00.000");
+        assert_eq!(lines[2], "ORIGINATOR = TLETOOLS");
+
+        let meta_start = lines.iter().position(|&l| l == "META_START").unwrap();
+        let meta_stop = lines.iter().position(|&l| l == "META_STOP").unwrap();
+        let metadata = &lines[meta_start + 1..meta_stop];
+        assert!(metadata.contains(&"OBJECT_NAME = ISS (ZARYA)"));
+        assert!(metadata.contains(&"OBJECT_ID = 1998-067A"));
+        assert!(metadata.contains(&"CENTER_NAME = EARTH"));
+        assert!(metadata.contains(&"REF_FRAME = TEME"));
+        assert!(metadata.contains(&"TIME_SYSTEM = UTC"));
+        assert!(metadata.contains(&"START_TIME = 2020-02-14T04:27:39.231"));
+        assert!(metadata.contains(&"STOP_TIME = 2020-02-14T04:37:39.231"));
+
+        let data: Vec<&str> = lines[meta_stop + 1..]
+            .iter()
+            .copied()
+            .filter(|l| !l.is_empty())
+            .collect();
+        assert_eq!(data.len(), 11);
+        for line in data {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            assert_eq!(fields.len(), 7);
+            let r: f64 = fields[1..4]
+                .iter()
+                .map(|x| x.parse::<f64>().unwrap().powi(2))
+                .sum::<f64>()
+                .sqrt();
+            assert!(6700.0 < r && r < 6850.0);
+        }
+
+        // line breaks in the values would start new lines
+        let tle = TLE {
+            name: "ISS\nZARYA".to_string(),
+            ..tle
+        };
+        let options = OemOptions {
+            originator: "A\r\nB".to_string(),
+            ..options
+        };
+        let oem = tle.to_oem(&options).unwrap();
+        let lines: Vec<&str> = oem.lines().collect();
+        assert_eq!(lines[2], "ORIGINATOR = A  B");
+        assert!(lines.contains(&"OBJECT_NAME = ISS ZARYA"));
+    }
+
+    #[test]
+    fn test_xml() {
+        let (tle, options) = options(OemFormat::Xml);
+        let oem = tle.to_oem(&options).unwrap();
+
+        assert!(oem.starts_with("<?xml"));
+        assert!(oem.trim_end().ends_with("</oem>"));
+        assert!(oem.contains("<OBJECT_ID>1998-067A</OBJECT_ID>"));
+        assert!(oem.contains("<REF_FRAME>TEME</REF_FRAME>"));
+        assert_eq!(oem.matches("<stateVector>").count(), 11);
+        assert_eq!(oem.matches("</stateVector>").count(), 11);
+        for tag in ["header", "body", "segment", "metadata", "data"] {
+            assert_eq!(oem.matches(&format!("<{}>", tag)).count(), 1);
+            assert_eq!(oem.matches(&format!("</{}>", tag)).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_invalid_time_span() {
+        let (tle, options) = options(OemFormat::Kvn);
+        let start = options.start;
+        for (stop, step) in [
+            (options.stop, 0.0),
+            (f64::INFINITY, options.step),
+            (f64::NAN, options.step),
+            (start - 1.0, options.step),
+            (start + 1.0, 1e-12),
+        ] {
+            let options = OemOptions {
+                stop,
+                step,
+                ..options.clone()
+            };
+            assert!(matches!(
+                tle.to_oem(&options),
+                Err(OemError::InvalidTimeSpan(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::f64::consts::PI;
+
+use thiserror::Error;
+
+use crate::{StateVector, TLEKey, TLE};
+
+/// Cause of a propagation failure, numbered like the error codes of the
+/// reference SGP4 implementation (Vallado et al., *Revisiting Spacetrack Report #3*).
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropagationErrorKind {
+    /// The mean eccentricity is out of range, at the epoch or after the secular and drag updates.
+    #[error("mean eccentricity out of range")]
+    MeanEccentricity,
+    /// The mean motion is not positive.
+    #[error("mean motion is not positive")]
+    MeanMotion,
+    /// The eccentricity perturbed by the long-period terms is out of range.
+    #[error("perturbed eccentricity out of range")]
+    PerturbedEccentricity,
+    /// The semi-latus rectum is negative.
+    #[error("negative semi-latus rectum")]
+    SemiLatusRectum,
+    /// The perigee of the elements at the epoch is below the surface of the Earth.
+    #[error("epoch elements are sub-orbital")]
+    SubOrbital,
+    /// The satellite is below the surface of the Earth.
+    #[error("satellite has decayed")]
+    Decayed,
+    /// An error of the `sgp4` crate which does not match any of the above.
+    #[error("propagation failed")]
+    Other,
+}
+
+impl PropagationErrorKind {
+    /// Error code of the reference implementation, from 1 to 6, or 0 for [`Other`](Self::Other).
+    pub fn code(self) -> u8 {
+        match self {
+            PropagationErrorKind::MeanEccentricity => 1,
+            PropagationErrorKind::MeanMotion => 2,
+            PropagationErrorKind::PerturbedEccentricity => 3,
+            PropagationErrorKind::SemiLatusRectum => 4,
+            PropagationErrorKind::SubOrbital => 5,
+            PropagationErrorKind::Decayed => 6,
+            PropagationErrorKind::Other => 0,
+        }
+    }
+
+    /// Classify an error of the `sgp4` crate, which only carries a message.
+    ///
+    /// The messages are those of the version pinned in `Cargo.toml`.
+    fn from_sgp4(error: &sgp4::Error) -> PropagationErrorKind {
+        match error.to_string().as_str() {
+            "the eccentricity must be in the range [0, 1[" | "diverging eccentricity" => {
+                PropagationErrorKind::MeanEccentricity
+            }
+            "the Kozai mean motion must be positive"
+            | "the Brouwer mean motion must be positive" => PropagationErrorKind::MeanMotion,
+            "diverging perturbed eccentricity" => PropagationErrorKind::PerturbedEccentricity,
+            "negative semi-latus rectum" => PropagationErrorKind::SemiLatusRectum,
+            _ => PropagationErrorKind::Other,
+        }
+    }
+}
+
+/// Failure of the propagation of a TLE.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{kind} (object {}, code {})", .key.norad, .kind.code())]
+pub struct PropagationError {
+    /// Identity of the propagated element set.
+    pub key: TLEKey,
+    /// Cause of the failure.
+    pub kind: PropagationErrorKind,
+}
+
+/// SGP4 propagator initialized from a [`TLE`].
+///
+/// Positions and velocities are expressed in the TEME (True Equator, Mean Equinox) frame.
+pub struct Propagator {
+    constants: sgp4::Constants<'static>,
+    epoch: f64,
+    key: TLEKey,
+}
+
+impl TLE {
+    /// Initialize the SGP4 propagator for this TLE (WGS-84 geopotential).
+    ///
+    /// Elements whose perigee is below the surface of the Earth are rejected
+    /// with [`PropagationErrorKind::SubOrbital`]. The reference implementation
+    /// only flags them and still propagates, so this is stricter: such TLEs
+    /// cannot be propagated at all.
+    pub fn propagator(&self) -> Result<Propagator, PropagationError> {
+        let error = |kind| PropagationError {
+            key: self.key(),
+            kind,
+        };
+        let sgp4_error = |e: sgp4::Error| error(PropagationErrorKind::from_sgp4(&e));
+
+        let deg = PI / 180.0;
+        let orbit = sgp4::Orbit::from_kozai_elements(
+            &sgp4::WGS84,
+            self.inc * deg,
+            self.raan * deg,
+            self.ecc,
+            self.argp * deg,
+            self.M * deg,
+            self.n * (PI / 720.0),
+        )
+        .map_err(sgp4_error)?;
+        // perigee radius in earth radii, from the Brouwer mean motion
+        let a = (sgp4::WGS84.ke / orbit.mean_motion).powf(2.0 / 3.0);
+        if a * (1.0 - orbit.eccentricity) < 1.0 {
+            return Err(error(PropagationErrorKind::SubOrbital));
+        }
+        let constants = sgp4::Constants::new(
+            &sgp4::WGS84,
+            sgp4::iau_epoch_to_sidereal_time,
+            self.epoch_j2000_years(),
+            self.bstar,
+            orbit,
+        )
+        .map_err(sgp4_error)?;
+        Ok(Propagator {
+            constants,
+            epoch: self.epoch_julian_date(),
+            key: self.key(),
+        })
+    }
+}
+
+impl Propagator {
+    /// Julian date (UTC) of the epoch of the TLE.
+    pub fn epoch_julian_date(&self) -> f64 {
+        self.epoch
+    }
+
+    /// Identity of the propagated element set.
+    pub fn key(&self) -> &TLEKey {
+        &self.key
+    }
+
+    /// Propagate to the given number of minutes since the epoch.
+    pub fn propagate(&self, minutes: f64) -> Result<StateVector, PropagationError> {
+        let error = |kind| PropagationError {
+            key: self.key.clone(),
+            kind,
+        };
+        let prediction = self
+            .constants
+            .propagate(minutes)
+            .map_err(|e| error(PropagationErrorKind::from_sgp4(&e)))?;
+        let [x, y, z] = prediction.position;
+        if (x * x + y * y + z * z).sqrt() < sgp4::WGS84.ae {
+            return Err(error(PropagationErrorKind::Decayed));
+        }
+        Ok(StateVector {
+            position: prediction.position,
+            velocity: prediction.velocity,
+        })
+    }
+
+    /// Propagate to the given Julian date (UTC).
+    pub fn propagate_to(&self, jd: f64) -> Result<StateVector, PropagationError> {
+        self.propagate((jd - self.epoch) * crate::epoch::MINUTES_PER_DAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_error_codes() {
+        let iss = || crate::parse(ISS).unwrap();
+        let init_error = |tle: TLE| tle.propagator().err().map(|e| e.kind.code());
+        assert_eq!(init_error(iss()), None);
+        assert_eq!(init_error(TLE { ecc: 1.5, ..iss() }), Some(1));
+        assert_eq!(init_error(TLE { n: -1.0, ..iss() }), Some(2));
+        assert_eq!(init_error(TLE { n: 17.5, ..iss() }), Some(5));
+        let other = sgp4::Error::new("unknown".to_string());
+        assert_eq!(
+            PropagationErrorKind::from_sgp4(&other),
+            PropagationErrorKind::Other
+        );
+
+        // first error within a week
+        let first_error = |tle: TLE| {
+            let propagator = tle.propagator().unwrap();
+            assert_eq!(propagator.key(), &tle.key());
+            let error = (0..10_080)
+                .find_map(|minutes| propagator.propagate(minutes as f64).err())
+                .unwrap();
+            assert_eq!(error.key, tle.key());
+            error.kind
+        };
+        // the drag makes the orbit decay within a day
+        let decaying = |n, bstar| TLE { n, bstar, ..iss() };
+        assert_eq!(
+            first_error(decaying(15.49165514, 0.5)),
+            PropagationErrorKind::Decayed
+        );
+        assert_eq!(
+            first_error(decaying(16.3, 0.05)),
+            PropagationErrorKind::MeanEccentricity
+        );
+
+        // deep-space case 33334 of the verification set of the reference implementation
+        let tle = crate::parse(
+            "1 33334U 78066F   06174.85818871  .00000620  00000-0  10000-3 0  6806
+2 33334  68.4714 236.1303 5602877 123.7484 302.5767  0.00001000 67521",
+        )
+        .unwrap();
+        assert_eq!(
+            first_error(tle),
+            PropagationErrorKind::PerturbedEccentricity
+        );
+        // a very eccentric orbit, whose perigee is lowered by a negative drag term
+        let tle = TLE {
+            n: 0.01,
+            ecc: 0.9,
+            bstar: -1.0,
+            inc: 98.0,
+            argp: 0.0,
+            ..iss()
+        };
+        assert_eq!(first_error(tle), PropagationErrorKind::SemiLatusRectum);
+    }
+}
@@ -0,0 +1,406 @@
+//! Reading of files containing many TLE records.
+//!
+//! The [`Reader`] consumes the input line by line and assembles the records,
+//! recognising their [`Format`]. Blank lines are skipped. When a record is
+//! malformed an error is returned and reading resumes from the next line
+//! that can start a new record, so a single bad record does not prevent
+//! reading the rest of the file.
+//!
+//! The input does not need to be valid UTF-8: names are decoded as UTF-8 if
+//! valid and as Latin-1 otherwise, while element lines containing non-ASCII
+//! bytes are rejected with [`Error::NonAsciiLine`] (see [`crate::from_line_bytes`]).
+//! The element lines are decoded with the [`fast`] decoder.
+//!
+//! With the `tokio` feature, the [`AsyncReader`] provides the same records
+//! as a [`Stream`](futures_core::Stream) over an asynchronous reader.
+
+use std::io::{self, BufRead};
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{ready, Context, Poll};
+
+#[cfg(feature = "tokio")]
+use futures_core::Stream;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, Split};
+
+use thiserror::Error;
+
+use crate::format::{is_line1, is_line2};
+use crate::name::decode_name;
+use crate::{fast, Error, Format, TLE};
+
+#[derive(Error, Debug)]
+pub enum ReadError {
+    #[error("I/O error")]
+    IoError(#[from] io::Error),
+    #[error("invalid record at line {line}")]
+    InvalidRecord {
+        /// Number (starting from 1) of the first line of the record.
+        line: usize,
+        #[source]
+        source: Error,
+    },
+}
+
+impl ReadError {
+    fn invalid(line: usize, reason: &'static str) -> ReadError {
+        ReadError::InvalidRecord {
+            line,
+            source: Error::InvalidFormat(reason),
+        }
+    }
+}
+
+/// State machine assembling TLE records from individual lines.
+///
+/// It is shared by the synchronous and asynchronous readers, so that they
+/// have the same resynchronization and error semantics.
+#[derive(Debug, Default)]
+pub(crate) struct Assembler {
+    /// Expected format, or `None` to detect it for each record.
+    expected: Option<Format>,
+    /// Format of the last record.
+    detected: Option<Format>,
+    /// Number of lines consumed so far.
+    line_no: usize,
+    /// Pending name line and its number.
+    name: Option<(usize, Vec<u8>)>,
+    /// Pending first line and the number of the first line of the record.
+    line1: Option<(usize, Vec<u8>)>,
+}
+
+impl Assembler {
+    pub(crate) fn new(expected: Option<Format>) -> Assembler {
+        Assembler {
+            expected,
+            ..Assembler::default()
+        }
+    }
+
+    pub(crate) fn detected(&self) -> Option<Format> {
+        self.detected
+    }
+
+    /// Feed a line, returning the record or error it completes, if any.
+    pub(crate) fn push(&mut self, line: &[u8]) -> Option<Result<TLE, ReadError>> {
+        self.line_no += 1;
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        if line.trim_ascii().is_empty() {
+            return None;
+        }
+
+        if is_line1(line) {
+            let incomplete = self.line1.take().map(|(start, _)| start);
+            let start = self.name.as_ref().map_or(self.line_no, |(start, _)| *start);
+            self.line1 = Some((start, line.to_vec()));
+            return incomplete.map(|start| Err(ReadError::invalid(start, "missing line 2")));
+        }
+
+        if is_line2(line) {
+            return match self.line1.take() {
+                Some((start, line1)) => {
+                    let name = self.name.take().map(|(_, name)| name);
+                    Some(self.record(start, name, &line1, line))
+                }
+                None => {
+                    let start = self.name.take().map_or(self.line_no, |(start, _)| start);
+                    Some(Err(ReadError::invalid(start, "missing line 1")))
+                }
+            };
+        }
+
+        // a name line starts a new record
+        let pending = match (self.name.take(), self.line1.take()) {
+            (_, Some((start, _))) => Some(ReadError::invalid(start, "missing line 2")),
+            (Some((start, _)), None) => Some(ReadError::invalid(start, "missing lines 1 and 2")),
+            (None, None) => None,
+        };
+        self.name = Some((self.line_no, line.to_vec()));
+        pending.map(Err)
+    }
+
+    /// Signal the end of the input, returning the error for an incomplete record, if any.
+    pub(crate) fn finish(&mut self) -> Option<Result<TLE, ReadError>> {
+        match (self.name.take(), self.line1.take()) {
+            (_, Some((start, _))) => Some(Err(ReadError::invalid(start, "missing line 2"))),
+            (Some((start, _)), None) => {
+                Some(Err(ReadError::invalid(start, "missing lines 1 and 2")))
+            }
+            (None, None) => None,
+        }
+    }
+
+    fn record(
+        &mut self,
+        start: usize,
+        name: Option<Vec<u8>>,
+        line1: &[u8],
+        line2: &[u8],
+    ) -> Result<TLE, ReadError> {
+        let name = name.as_deref().map(decode_name);
+        let format = Format::detect(name.as_deref());
+        match self.expected {
+            Some(Format::TwoLine) if format != Format::TwoLine => {
+                return Err(ReadError::invalid(start, "unexpected name line"));
+            }
+            Some(Format::SpaceTrack) if format != Format::SpaceTrack => {
+                return Err(ReadError::invalid(start, "name line without `0 ` prefix"));
+            }
+            Some(Format::ThreeLine | Format::CelesTrak) if format == Format::TwoLine => {
+                return Err(ReadError::invalid(start, "missing name line"));
+            }
+            _ => {}
+        }
+        let name = format.name(name.as_deref().unwrap_or(""));
+        let tle = fast::from_line_bytes(name.as_bytes(), line1, line2).map_err(|source| {
+            ReadError::InvalidRecord {
+                line: start,
+                source,
+            }
+        })?;
+        self.detected = Some(format);
+        Ok(tle)
+    }
+}
+
+/// Iterator over the TLE records read from a buffered reader.
+pub struct Reader<R> {
+    reader: R,
+    assembler: Assembler,
+    buf: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Create a reader detecting the format of each record.
+    pub fn new(reader: R) -> Reader<R> {
+        Reader {
+            reader,
+            assembler: Assembler::new(None),
+            buf: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Create a reader accepting only records in the given format.
+    pub fn with_format(reader: R, format: Format) -> Reader<R> {
+        Reader {
+            assembler: Assembler::new(Some(format)),
+            ..Reader::new(reader)
+        }
+    }
+
+    /// Format of the last record read successfully.
+    pub fn format(&self) -> Option<Format> {
+        self.assembler.detected()
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<TLE, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => {
+                    self.done = true;
+                    return self.assembler.finish();
+                }
+                Ok(_) => {
+                    if let Some(result) = self.assembler.push(&self.buf) {
+                        return Some(result);
+                    }
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        None
+    }
+}
+
+/// Parse all the TLE records contained in a string.
+pub fn parse_all(s: &str) -> Reader<&[u8]> {
+    Reader::new(s.as_bytes())
+}
+
+/// Stream of the TLE records read from an asynchronous buffered reader.
+///
+/// The records and the errors are the same as those of a [`Reader`] on the same input.
+#[cfg(feature = "tokio")]
+pub struct AsyncReader<R> {
+    lines: Split<R>,
+    assembler: Assembler,
+    done: bool,
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncBufRead + Unpin> AsyncReader<R> {
+    /// Create a reader detecting the format of each record.
+    pub fn new(reader: R) -> AsyncReader<R> {
+        AsyncReader {
+            lines: reader.split(b'\n'),
+            assembler: Assembler::new(None),
+            done: false,
+        }
+    }
+
+    /// Create a reader accepting only records in the given format.
+    pub fn with_format(reader: R, format: Format) -> AsyncReader<R> {
+        AsyncReader {
+            assembler: Assembler::new(Some(format)),
+            ..AsyncReader::new(reader)
+        }
+    }
+
+    /// Format of the last record read successfully.
+    pub fn format(&self) -> Option<Format> {
+        self.assembler.detected()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncBufRead + Unpin> Stream for AsyncReader<R> {
+    type Item = Result<TLE, ReadError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            match ready!(Pin::new(&mut this.lines).poll_next_segment(cx)) {
+                Ok(None) => {
+                    this.done = true;
+                    return Poll::Ready(this.assembler.finish());
+                }
+                Ok(Some(line)) => {
+                    if let Some(result) = this.assembler.push(&line) {
+                        return Poll::Ready(Some(result));
+                    }
+                }
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990";
+    const LINE2: &str = "2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_formats() {
+        let input = format!(
+            "ISS (ZARYA)\n{LINE1}\n{LINE2}\n\
+             0 ISS (ZARYA)\n{LINE1}\n{LINE2}\n\
+             ISS (ZARYA)             \n{LINE1}\n{LINE2}\n\
+             {LINE1}\n{LINE2}\n"
+        );
+        let mut reader = parse_all(&input);
+        for (format, name) in [
+            (Format::ThreeLine, "ISS (ZARYA)"),
+            (Format::SpaceTrack, "ISS (ZARYA)"),
+            (Format::CelesTrak, "ISS (ZARYA)"),
+            (Format::TwoLine, ""),
+        ] {
+            let tle = reader.next().unwrap().unwrap();
+            assert_eq!(tle.name, name);
+            assert_eq!(reader.format(), Some(format));
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_resynchronization() {
+        let input = format!(
+            "BROKEN\n{LINE1}\n\
+             ISS (ZARYA)\n{LINE1}\n{LINE2}\n\
+             \n\
+             ORPHAN\n\
+             {LINE2}\n\
+             ISS (ZARYA)\n{LINE1}\r\n{LINE2}\r\n\
+             TRAILING\n"
+        );
+        let results: Vec<_> = parse_all(&input).collect();
+        assert_eq!(results.len(), 5);
+        assert!(matches!(
+            results[0],
+            Err(ReadError::InvalidRecord { line: 1, .. })
+        ));
+        assert!(results[1].is_ok());
+        assert!(matches!(
+            results[2],
+            Err(ReadError::InvalidRecord { line: 7, .. })
+        ));
+        assert!(results[3].is_ok());
+        assert!(matches!(
+            results[4],
+            Err(ReadError::InvalidRecord { line: 12, .. })
+        ));
+    }
+
+    #[test]
+    fn test_expected_format() {
+        let input = format!("ISS (ZARYA)\n{LINE1}\n{LINE2}\n{LINE1}\n{LINE2}\n");
+        let results: Vec<_> = Reader::with_format(input.as_bytes(), Format::TwoLine).collect();
+        assert!(results[0].is_err());
+        assert!(results[1].is_ok());
+
+        let results: Vec<_> = Reader::with_format(input.as_bytes(), Format::SpaceTrack).collect();
+        assert!(results.iter().all(|r| r.is_err()));
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let mut input = b"S\xc3O PAULO\n".to_vec();
+        input.extend_from_slice(format!("{LINE1}\n{LINE2}\n").as_bytes());
+        // a Latin-1 middle dot in place of the checksum
+        input.extend_from_slice(&LINE1.as_bytes()[..68]);
+        input.extend_from_slice(b"\xb7\n");
+        input.extend_from_slice(format!("{LINE2}\n").as_bytes());
+        let results: Vec<_> = Reader::new(&input[..]).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().name, "SÃO PAULO");
+        assert!(matches!(
+            results[1],
+            Err(ReadError::InvalidRecord {
+                line: 4,
+                source: Error::NonAsciiLine(1)
+            })
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_reader() {
+        use tokio_stream::StreamExt;
+
+        let input = format!(
+            "BROKEN\n{LINE1}\n\
+             0 ISS (ZARYA)\n{LINE1}\n{LINE2}\n\
+             ORPHAN\n{LINE2}\n\
+             {LINE1}\r\n{LINE2}\r\n"
+        );
+        let expected: Vec<_> = parse_all(&input)
+            .map(|r| r.map_err(|e| e.to_string()))
+            .collect();
+        let mut reader = AsyncReader::new(input.as_bytes());
+        let mut results = Vec::new();
+        while let Some(result) = reader.next().await {
+            results.push(result.map_err(|e| e.to_string()));
+        }
+        assert_eq!(results, expected);
+        assert_eq!(results.len(), 4);
+        assert_eq!(reader.format(), Some(Format::TwoLine));
+
+        let reader = AsyncReader::with_format(input.as_bytes(), Format::SpaceTrack);
+        let results: Vec<_> = reader.collect().await;
+        assert!(results[1].is_ok() && results[3].is_err());
+    }
+}
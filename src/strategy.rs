@@ -0,0 +1,150 @@
+//! `proptest` strategies generating valid TLEs, and the properties of the parsers.
+
+use proptest::prelude::*;
+
+use crate::math::scale10;
+use crate::{Classification, EphemerisType, IntDesig, TLE};
+
+/// Fixed-point value with the given number of decimals, as it would be parsed.
+fn fixed(range: core::ops::RangeInclusive<i64>, decimals: i32) -> impl Strategy<Value = f64> {
+    range.prop_map(move |k| scale10(k as f64, -decimals))
+}
+
+/// Value in the decimal-point-assumed exponential notation, with a normalized mantissa.
+fn exp_decimal() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        Just(0.0),
+        (10_000..=99_999i32, any::<bool>(), -9..=9i32)
+            .prop_map(|(m, negative, e)| scale10((if negative { -m } else { m }) as f64, e - 5)),
+    ]
+}
+
+pub(crate) fn int_desig() -> impl Strategy<Value = IntDesig> {
+    (1957..=2056i32, 1..=999u16, "[A-Z]{1,3}")
+        .prop_map(|(year, launch, piece)| IntDesig::new(year, launch, &piece).unwrap())
+}
+
+/// TLEs whose fields are exactly representable in the TLE format.
+pub(crate) fn tle() -> impl Strategy<Value = TLE> {
+    let header = (
+        "[A-Z][A-Z0-9 ()/-]{0,22}[A-Z0-9)]",
+        "[0-9]{5}",
+        prop_oneof![
+            Just(Classification::Unclassified),
+            Just(Classification::Classified),
+            Just(Classification::Secret),
+        ],
+        proptest::option::of(int_desig()),
+        1957..=2056i32,
+        fixed(100_000_000..=36_699_999_999, 8),
+        fixed(-99_999_999..=99_999_999, 8),
+        exp_decimal(),
+        exp_decimal(),
+        "[0-9 ]".prop_map(|c| EphemerisType::try_from(c.chars().next().unwrap()).unwrap()),
+        0..=9999u32,
+    );
+    let elements = (
+        fixed(0..=1_800_000, 4),
+        fixed(0..=3_599_999, 4),
+        fixed(0..=9_999_999, 7),
+        fixed(0..=3_599_999, 4),
+        fixed(0..=3_599_999, 4),
+        fixed(1..=9_999_999_999, 8),
+        0..=99_999u32,
+    );
+    (header, elements).prop_map(
+        |(
+            (
+                name,
+                norad,
+                classification,
+                int_desig,
+                epoch_year,
+                epoch_day,
+                dn_o2,
+                ddn_o6,
+                bstar,
+                ephem_type,
+                set_num,
+            ),
+            (inc, raan, ecc, argp, m, n, rev_num),
+        )| TLE {
+            name,
+            norad,
+            classification,
+            int_desig: int_desig.into(),
+            epoch_year,
+            epoch_day,
+            dn_o2,
+            ddn_o6,
+            bstar,
+            ephem_type,
+            set_num,
+            inc,
+            raan,
+            ecc,
+            argp,
+            M: m,
+            n,
+            rev_num,
+        },
+    )
+}
+
+/// Lines of the right length made of arbitrary characters, mostly from the TLE alphabet.
+fn noisy_line(first: char) -> impl Strategy<Value = String> {
+    proptest::collection::vec(
+        prop_oneof![
+            8 => "[0-9 .+-]",
+            1 => "[A-Z]",
+            1 => any::<char>().prop_map(String::from),
+        ],
+        68,
+    )
+    .prop_map(move |chars| core::iter::once(first.to_string()).chain(chars).collect())
+}
+
+proptest! {
+    #[test]
+    fn format_parse_round_trip(tle in tle()) {
+        prop_assert_eq!(tle.check_encodable(crate::EncodePolicy::STRICT), Ok(()));
+        for format in [
+            crate::Format::ThreeLine,
+            crate::Format::SpaceTrack,
+            crate::Format::CelesTrak,
+        ] {
+            prop_assert_eq!(&crate::parse(&tle.to_string_in(format)).unwrap(), &tle);
+        }
+        prop_assert_eq!(
+            &crate::fast::from_lines(&tle.name, &tle.line1(), &tle.line2()).unwrap(),
+            &tle
+        );
+        let exact = crate::ExactTLE::from_lines(&tle.name, &tle.line1(), &tle.line2()).unwrap();
+        prop_assert_eq!(exact.line1(), tle.line1());
+        prop_assert_eq!(exact.line2(), tle.line2());
+    }
+
+    #[test]
+    fn parse_does_not_panic(s in "\\PC*") {
+        let _ = crate::parse(&s);
+    }
+
+    #[test]
+    fn parse_bytes_does_not_panic(bytes in proptest::collection::vec(any::<u8>(), 0..300)) {
+        let _ = crate::parse_bytes(&bytes);
+    }
+
+    #[test]
+    fn from_lines_does_not_panic_and_fast_agrees(
+        name in "\\PC{0,30}",
+        line1 in noisy_line('1'),
+        line2 in noisy_line('2'),
+    ) {
+        prop_assert_eq!(
+            crate::fast::from_lines(&name, &line1, &line2),
+            crate::from_lines(&name, &line1, &line2)
+        );
+        #[cfg(feature = "nom")]
+        let _ = crate::nom::parse_single_tle(&format!("{}\n{}\n{}\n", name, line1, line2));
+    }
+}
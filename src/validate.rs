@@ -0,0 +1,183 @@
+//! Semantic validation of the elements of a TLE.
+//!
+//! Parsing only checks the syntax of the lines; [`TLE::validate`] checks that
+//! the values are in their ranges and that they describe a plausible orbit.
+
+use thiserror::Error;
+
+use crate::epoch::is_leap_year;
+use crate::TLE;
+
+/// Perigee altitude (km) below which an orbit decays within days.
+pub const LOW_PERIGEE_ALTITUDE: f64 = 120.0;
+
+/// Absolute value of BSTAR (1/earth radii) above which the drag is implausible.
+pub const MAX_PLAUSIBLE_BSTAR: f64 = 0.5;
+
+/// Whether a finding makes the TLE unusable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The values are valid but unusual.
+    Warning,
+    /// The values are out of range or describe an impossible orbit.
+    Error,
+}
+
+/// Problem found by [`TLE::validate`].
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum Finding {
+    #[error("day of the year {0} out of range")]
+    EpochDayOutOfRange(f64),
+    #[error("inclination {0} out of range [0, 180]")]
+    InclinationOutOfRange(f64),
+    #[error("right ascension of the ascending node {0} out of range [0, 360)")]
+    RaanOutOfRange(f64),
+    #[error("eccentricity {0} out of range [0, 1)")]
+    EccentricityOutOfRange(f64),
+    #[error("argument of perigee {0} out of range [0, 360)")]
+    ArgpOutOfRange(f64),
+    #[error("mean anomaly {0} out of range [0, 360)")]
+    MeanAnomalyOutOfRange(f64),
+    #[error("mean motion {0} is not positive")]
+    NonPositiveMeanMotion(f64),
+    #[error("perigee altitude {0} km is below the surface of the Earth")]
+    PerigeeBelowSurface(f64),
+    #[error("perigee altitude {0} km is very low")]
+    LowPerigee(f64),
+    #[error("BSTAR {0} is implausibly large")]
+    LargeBstar(f64),
+}
+
+impl Finding {
+    /// Severity of the finding.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Finding::LowPerigee(_) | Finding::LargeBstar(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Whether the finding makes the TLE unusable.
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl TLE {
+    /// Check that the elements are in range and describe a plausible orbit.
+    ///
+    /// Returns all the problems found, errors and warnings, in the order of the fields.
+    /// The orbit is only checked if the eccentricity and the mean motion are valid.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let angle = |x: f64| (0.0..360.0).contains(&x);
+
+        let days = if is_leap_year(self.epoch_year) {
+            366.0
+        } else {
+            365.0
+        };
+        if !(1.0..days + 1.0).contains(&self.epoch_day) {
+            findings.push(Finding::EpochDayOutOfRange(self.epoch_day));
+        }
+        if !(0.0..=180.0).contains(&self.inc) {
+            findings.push(Finding::InclinationOutOfRange(self.inc));
+        }
+        if !angle(self.raan) {
+            findings.push(Finding::RaanOutOfRange(self.raan));
+        }
+        let ecc_valid = (0.0..1.0).contains(&self.ecc);
+        if !ecc_valid {
+            findings.push(Finding::EccentricityOutOfRange(self.ecc));
+        }
+        if !angle(self.argp) {
+            findings.push(Finding::ArgpOutOfRange(self.argp));
+        }
+        if !angle(self.M) {
+            findings.push(Finding::MeanAnomalyOutOfRange(self.M));
+        }
+        let n_valid = self.n > 0.0;
+        if !n_valid {
+            findings.push(Finding::NonPositiveMeanMotion(self.n));
+        }
+
+        if ecc_valid && n_valid {
+            let perigee = self.perigee_altitude();
+            if perigee < 0.0 {
+                findings.push(Finding::PerigeeBelowSurface(perigee));
+            } else if perigee < LOW_PERIGEE_ALTITUDE {
+                findings.push(Finding::LowPerigee(perigee));
+            }
+        }
+        if self.bstar.abs() > MAX_PLAUSIBLE_BSTAR {
+            findings.push(Finding::LargeBstar(self.bstar));
+        }
+
+        findings
+    }
+
+    /// Whether [`validate`](TLE::validate) finds no errors (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        self.validate().iter().all(|f| !f.is_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   20045.18587073  .00000950  00000-0  25302-4 0  9990
+2 25544  51.6443 242.0161 0004885 264.6060 207.3845 15.49165514212791";
+
+    #[test]
+    fn test_valid() {
+        let tle = crate::parse(ISS).unwrap();
+        assert_eq!(tle.validate(), []);
+        assert!(tle.is_valid());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut tle = crate::parse(ISS).unwrap();
+        tle.epoch_day = 400.0;
+        tle.inc = 181.0;
+        tle.ecc = 1.2;
+        tle.n = -1.0;
+        assert_eq!(
+            tle.validate(),
+            [
+                Finding::EpochDayOutOfRange(400.0),
+                Finding::InclinationOutOfRange(181.0),
+                Finding::EccentricityOutOfRange(1.2),
+                Finding::NonPositiveMeanMotion(-1.0),
+            ]
+        );
+        assert!(!tle.is_valid());
+
+        tle = crate::parse(ISS).unwrap();
+        tle.epoch_year = 2020;
+        tle.epoch_day = 366.5;
+        assert!(tle.is_valid());
+        tle.epoch_year = 2021;
+        assert!(!tle.is_valid());
+    }
+
+    #[test]
+    fn test_plausibility() {
+        let mut tle = crate::parse(ISS).unwrap();
+        tle.ecc = 0.1;
+        let findings = tle.validate();
+        assert!(matches!(findings[..], [Finding::PerigeeBelowSurface(_)]));
+        assert_eq!(findings[0].severity(), Severity::Error);
+
+        tle.ecc = 0.05;
+        tle.bstar = 1.0;
+        let findings = tle.validate();
+        assert!(matches!(
+            findings[..],
+            [Finding::LowPerigee(_), Finding::LargeBstar(_)]
+        ));
+        assert!(tle.is_valid());
+    }
+}